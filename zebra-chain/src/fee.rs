@@ -0,0 +1,186 @@
+//! Bundle-oriented change and fee estimation, built on top of per-pool
+//! [`crate::value_balance::ValueBalance`] arithmetic.
+//!
+//! This mirrors the `zcash_client_backend`/librustzcash "bundle-wise" change
+//! strategy design: a [`ChangeStrategy`] is handed the transparent, Sapling,
+//! and Orchard inputs and outputs a transaction is spending, and decides how
+//! much change (if any) goes back to the spender, after paying its fee.
+
+use std::convert::TryFrom;
+
+use crate::amount::{Amount, Error, NegativeAllowed, NonNegative};
+
+/// The zatoshi value below which an input isn't worth spending: its fee cost
+/// would exceed (or come close to) its own value.
+pub const DUST_THRESHOLD: u64 = 1000;
+
+/// An error produced while computing a transaction's change and fee.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ChangeError {
+    /// The inputs don't cover the requested outputs plus the fee.
+    #[error("insufficient funds: {available:?} available, {required:?} required")]
+    InsufficientFunds {
+        /// The value available to cover `required`: the inputs' total across
+        /// all pools, or, when the inputs cover the outputs but not the fee,
+        /// the value left over after the outputs. Zero when an amount or sum
+        /// falls outside the valid amount range.
+        available: Amount<NonNegative>,
+        /// The value `available` falls short of: the outputs' total across
+        /// all pools, or, in the second case above, the fee. Zero when an
+        /// amount or sum falls outside the valid amount range.
+        required: Amount<NonNegative>,
+    },
+
+    /// One or more inputs are below [`DUST_THRESHOLD`], listed by index so
+    /// the caller can exclude them and retry.
+    #[error("dust inputs: {transparent:?} transparent, {sapling:?} sapling, {orchard:?} orchard")]
+    DustInputs {
+        /// Indices of the excluded transparent inputs.
+        transparent: Vec<usize>,
+        /// Indices of the excluded Sapling inputs.
+        sapling: Vec<usize>,
+        /// Indices of the excluded Orchard inputs.
+        orchard: Vec<usize>,
+    },
+}
+
+/// Decides how a transaction's remaining value, after its inputs and
+/// requested outputs balance out, is split between the fee and change
+/// returned to the spender.
+pub trait ChangeStrategy {
+    /// Returns the fee this strategy charges for a transaction with the given
+    /// shape.
+    fn fee(
+        &self,
+        transparent_inputs: usize,
+        sapling_inputs: usize,
+        sapling_outputs: usize,
+        orchard_actions: usize,
+    ) -> Amount<NonNegative>;
+
+    /// Returns the change value to return to the spender, given the
+    /// transaction's `remaining_transaction_value` and this strategy's `fee`.
+    fn change(
+        &self,
+        remaining_transaction_value: Amount<NonNegative>,
+        fee: Amount<NonNegative>,
+    ) -> Result<Amount<NonNegative>, ChangeError> {
+        (remaining_transaction_value - fee).map_err(|_| ChangeError::InsufficientFunds {
+            available: remaining_transaction_value,
+            required: fee,
+        })
+    }
+}
+
+/// Computes the change a transaction should return to its spender.
+///
+/// Computes `inputs - outputs` for each of the transparent, Sapling, and
+/// Orchard pools via [`pool_balance`], then sums those three per-pool
+/// balances directly into the transaction's total remaining value. Unlike
+/// [`crate::value_balance::ValueBalance::remaining_transaction_value`], which
+/// checks the *consensus* rule that transparent value balance minus shielded
+/// value balance is nonnegative (a different, netted sign convention), this
+/// sums all three pools as plain "funds available" so a shielding or
+/// deshielding transaction's fee comes out correctly.
+///
+/// Returns [`ChangeError::DustInputs`] without consulting `strategy` if any
+/// input is below [`DUST_THRESHOLD`], and [`ChangeError::InsufficientFunds`]
+/// if the inputs don't cover the outputs plus the fee.
+pub fn compute_balance<S: ChangeStrategy>(
+    strategy: &S,
+    transparent_inputs: &[Amount<NonNegative>],
+    transparent_outputs: &[Amount<NonNegative>],
+    sapling_inputs: &[Amount<NonNegative>],
+    sapling_outputs: &[Amount<NonNegative>],
+    orchard_inputs: &[Amount<NonNegative>],
+    orchard_outputs: &[Amount<NonNegative>],
+) -> Result<Amount<NonNegative>, ChangeError> {
+    let dust_transparent = dust_indices(transparent_inputs);
+    let dust_sapling = dust_indices(sapling_inputs);
+    let dust_orchard = dust_indices(orchard_inputs);
+
+    if !dust_transparent.is_empty() || !dust_sapling.is_empty() || !dust_orchard.is_empty() {
+        return Err(ChangeError::DustInputs {
+            transparent: dust_transparent,
+            sapling: dust_sapling,
+            orchard: dust_orchard,
+        });
+    }
+
+    let transparent_balance = pool_balance(transparent_inputs, transparent_outputs)?;
+    let sapling_balance = pool_balance(sapling_inputs, sapling_outputs)?;
+    let orchard_balance = pool_balance(orchard_inputs, orchard_outputs)?;
+
+    let total_inputs = [transparent_inputs, sapling_inputs, orchard_inputs].concat();
+    let total_outputs = [transparent_outputs, sapling_outputs, orchard_outputs].concat();
+
+    let remaining = (transparent_balance + sapling_balance + orchard_balance)
+        .ok()
+        .and_then(|total: Amount<NegativeAllowed>| total.constrain::<NonNegative>().ok())
+        .ok_or_else(|| ChangeError::InsufficientFunds {
+            available: sum(&total_inputs)
+                .unwrap_or_else(|_| Amount::try_from(0).expect("an amount of 0 is always valid")),
+            required: sum(&total_outputs)
+                .unwrap_or_else(|_| Amount::try_from(0).expect("an amount of 0 is always valid")),
+        })?;
+
+    let fee = strategy.fee(
+        transparent_inputs.len(),
+        sapling_inputs.len(),
+        sapling_outputs.len(),
+        orchard_inputs.len() + orchard_outputs.len(),
+    );
+
+    strategy.change(remaining, fee)
+}
+
+/// Indices of `amounts` whose value is below [`DUST_THRESHOLD`].
+fn dust_indices(amounts: &[Amount<NonNegative>]) -> Vec<usize> {
+    amounts
+        .iter()
+        .enumerate()
+        .filter(|(_, amount)| u64::from(**amount) < DUST_THRESHOLD)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Sums `amounts`, rejecting a total that overflows the valid amount range.
+fn sum(amounts: &[Amount<NonNegative>]) -> Result<Amount<NonNegative>, ChangeError> {
+    amounts
+        .iter()
+        .copied()
+        .try_fold(
+            Amount::<NonNegative>::try_from(0).expect("an amount of 0 is always valid"),
+            |acc, amount| acc + amount,
+        )
+        .map_err(|_| ChangeError::InsufficientFunds {
+            available: Amount::try_from(0).expect("an amount of 0 is always valid"),
+            required: Amount::try_from(0).expect("an amount of 0 is always valid"),
+        })
+}
+
+/// Returns `inputs - outputs` for a single pool, allowed to go negative: a
+/// pool whose outputs exceed its inputs is fine on its own, it's the
+/// transaction-wide total (the sum of all three pools' balances) that must
+/// stay non-negative.
+fn pool_balance(
+    inputs: &[Amount<NonNegative>],
+    outputs: &[Amount<NonNegative>],
+) -> Result<Amount<NegativeAllowed>, ChangeError> {
+    let input_total = sum(inputs)?.constrain::<NegativeAllowed>()?;
+    let output_total = sum(outputs)?.constrain::<NegativeAllowed>()?;
+
+    (input_total - output_total).map_err(|_| ChangeError::InsufficientFunds {
+        available: sum(inputs)?,
+        required: sum(outputs)?,
+    })
+}
+
+impl From<Error> for ChangeError {
+    fn from(_: Error) -> Self {
+        ChangeError::InsufficientFunds {
+            available: Amount::try_from(0).expect("an amount of 0 is always valid"),
+            required: Amount::try_from(0).expect("an amount of 0 is always valid"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::unit_arg)]
 use std::fmt;
 
+use blake2b_simd::Params as Blake2bParams;
 #[cfg(any(test, feature = "proptest-impl"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,12 @@ pub struct Hash(pub [u8; 32]);
 
 impl<'a> From<&'a Transaction> for Hash {
     fn from(transaction: &'a Transaction) -> Self {
+        if transaction.version() >= 5 {
+            return TxIdDigester::new(transaction).txid();
+        }
+
+        // Pre-V5 transactions use the legacy, malleable wtxid-style hash: the
+        // double-SHA256 of the whole serialized transaction, signatures and all.
         let mut hash_writer = sha256d::Writer::default();
         transaction
             .zcash_serialize(&mut hash_writer)
@@ -27,6 +34,416 @@ impl<'a> From<&'a Transaction> for Hash {
     }
 }
 
+/// The personalization prefix used for the top-level ZIP-244 txid hash.
+///
+/// The active consensus branch id, as 4 little-endian bytes, is appended to
+/// this prefix to make up the full 16-byte BLAKE2b personalization.
+const ZCASH_TX_PERSONALIZATION_PREFIX: &[u8; 12] = b"ZcashTxHash_";
+
+/// The ZIP-225 transaction version group id for V5 transactions.
+const ZIP225_VERSION_GROUP_ID: u32 = 0x26A7_270A;
+
+/// ZIP-244 personalization for the header digest.
+const ZCASH_HEADERS_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdHeadersHash";
+/// ZIP-244 personalization for the transparent digest.
+const ZCASH_TRANSPARENT_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdTranspaHash";
+/// ZIP-244 personalization for the transparent prevouts sub-digest.
+const ZCASH_PREVOUTS_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdPrevoutHash";
+/// ZIP-244 personalization for the transparent sequence numbers sub-digest.
+const ZCASH_SEQUENCE_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdSequencHash";
+/// ZIP-244 personalization for the transparent outputs sub-digest.
+const ZCASH_OUTPUTS_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdOutputsHash";
+/// ZIP-244 personalization for the Sapling digest.
+const ZCASH_SAPLING_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdSaplingHash";
+/// ZIP-244 personalization for the Orchard digest.
+const ZCASH_ORCHARD_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdOrchardHash";
+/// ZIP-244 personalization for the authorizing data digest.
+const ZCASH_AUTH_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxAuthHash_____";
+/// ZIP-244 personalization for the transparent scriptSig sub-digest, within
+/// [`TxIdDigester::auth_digest`].
+const ZCASH_TRANSPARENT_AUTH_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdTranspaAuth";
+/// ZIP-244 personalization for the Sapling spend-auth and binding signature
+/// sub-digest, within [`TxIdDigester::auth_digest`].
+const ZCASH_SAPLING_AUTH_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdSaplingAuth";
+/// ZIP-244 personalization for the Orchard spend-auth and binding signature
+/// sub-digest, within [`TxIdDigester::auth_digest`].
+const ZCASH_ORCHARD_AUTH_HASH_PERSONALIZATION: &[u8; 16] = b"ZTxIdOrchardAuth";
+
+/// Returns a BLAKE2b hasher state with the given 16-byte `personalization`.
+fn hasher(personalization: &[u8; 16]) -> blake2b_simd::State {
+    Blake2bParams::new()
+        .hash_length(32)
+        .personal(personalization)
+        .to_state()
+}
+
+/// Hashes `value`'s serialized bytes with the given `personalization`, or
+/// returns the hash of an empty input if `value` is `None` — ZIP-244's
+/// convention for "this part of the transaction is absent".
+fn digest_or_empty<T: ZcashSerialize>(personalization: &[u8; 16], value: Option<&T>) -> [u8; 32] {
+    let mut state = hasher(personalization);
+
+    if let Some(value) = value {
+        let mut writer = Vec::new();
+        value
+            .zcash_serialize(&mut writer)
+            .expect("transaction parts must serialize for hashing");
+        state.update(&writer);
+    }
+
+    *state.finalize().as_array()
+}
+
+/// Builds the ZIP-244 digest tree for a V5 [`Transaction`], so the
+/// non-malleable txid and the V5 signature hash can share the same
+/// sub-digests instead of each recomputing them from scratch.
+pub struct TxIdDigester<'a> {
+    transaction: &'a Transaction,
+}
+
+impl<'a> TxIdDigester<'a> {
+    /// Creates a digester for `transaction`, which must be a V5 transaction.
+    pub fn new(transaction: &'a Transaction) -> Self {
+        Self { transaction }
+    }
+
+    /// Returns this transaction's ZIP-244 non-malleable transaction id.
+    pub fn txid(&self) -> Hash {
+        let mut personalization = [0; 16];
+        personalization[..12].copy_from_slice(ZCASH_TX_PERSONALIZATION_PREFIX);
+        personalization[12..].copy_from_slice(&self.branch_id().to_le_bytes());
+
+        let mut state = hasher(&personalization);
+        state.update(&self.header_digest());
+        state.update(&self.transparent_digest());
+        state.update(&self.sapling_digest());
+        state.update(&self.orchard_digest());
+
+        Hash(*state.finalize().as_array())
+    }
+
+    /// Returns the digest of this transaction's authorizing data: scriptSigs,
+    /// zero-knowledge proofs, and spend-auth/binding signatures.
+    ///
+    /// This is deliberately disjoint from [`TxIdDigester::txid`]'s inputs:
+    /// every field that can be changed by a party other than the transaction's
+    /// creator without invalidating it (a scriptSig, a Sapling/Orchard proof,
+    /// a spend-auth or binding signature) feeds this digest instead, so
+    /// malleating one changes `auth_digest` but never the txid.
+    pub fn auth_digest(&self) -> Hash {
+        let mut state = hasher(ZCASH_AUTH_HASH_PERSONALIZATION);
+        state.update(&self.transparent_auth_digest());
+        state.update(&self.sapling_auth_digest());
+        state.update(&self.orchard_auth_digest());
+        Hash(*state.finalize().as_array())
+    }
+
+    /// The transparent authorizing sub-digest: every input's scriptSig, in
+    /// order. Coinbase inputs have no scriptSig and contribute nothing.
+    fn transparent_auth_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+        for input in self.transaction.inputs() {
+            if let Some(unlock_script) = input.unlock_script() {
+                unlock_script
+                    .zcash_serialize(&mut writer)
+                    .expect("unlock scripts must serialize for hashing");
+            }
+        }
+
+        let mut state = hasher(ZCASH_TRANSPARENT_AUTH_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The Sapling authorizing sub-digest: every spend's zero-knowledge proof
+    /// and spend-auth signature, every output's zero-knowledge proof, and the
+    /// bundle's binding signature.
+    fn sapling_auth_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+
+        if let Some(sapling) = self.transaction.sapling_shielded_data() {
+            for spend in sapling.spends() {
+                spend
+                    .zkproof()
+                    .zcash_serialize(&mut writer)
+                    .expect("proofs must serialize for hashing");
+                spend
+                    .spend_auth_sig()
+                    .zcash_serialize(&mut writer)
+                    .expect("spend auth signatures must serialize for hashing");
+            }
+            for output in sapling.outputs() {
+                output
+                    .zkproof()
+                    .zcash_serialize(&mut writer)
+                    .expect("proofs must serialize for hashing");
+            }
+
+            sapling
+                .binding_sig()
+                .zcash_serialize(&mut writer)
+                .expect("binding signatures must serialize for hashing");
+        }
+
+        let mut state = hasher(ZCASH_SAPLING_AUTH_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The Orchard authorizing sub-digest: the bundle's shared
+    /// zero-knowledge proof, every action's spend-auth signature, and the
+    /// bundle's binding signature.
+    fn orchard_auth_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+
+        if let Some(orchard) = self.transaction.orchard_shielded_data() {
+            orchard
+                .proof()
+                .zcash_serialize(&mut writer)
+                .expect("proofs must serialize for hashing");
+
+            for action in orchard.actions() {
+                action
+                    .spend_auth_sig()
+                    .zcash_serialize(&mut writer)
+                    .expect("spend auth signatures must serialize for hashing");
+            }
+
+            orchard
+                .binding_sig()
+                .zcash_serialize(&mut writer)
+                .expect("binding signatures must serialize for hashing");
+        }
+
+        let mut state = hasher(ZCASH_ORCHARD_AUTH_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The header digest: version, group id, consensus branch id, lock time,
+    /// and expiry height.
+    fn header_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+        writer.extend_from_slice(&self.transaction.version().to_le_bytes());
+        writer.extend_from_slice(&ZIP225_VERSION_GROUP_ID.to_le_bytes());
+        writer.extend_from_slice(&self.branch_id().to_le_bytes());
+        self.transaction
+            .lock_time()
+            .zcash_serialize(&mut writer)
+            .expect("lock time must serialize for hashing");
+        if let Some(expiry_height) = self.transaction.expiry_height() {
+            writer.extend_from_slice(&expiry_height.0.to_le_bytes());
+        }
+
+        let mut state = hasher(ZCASH_HEADERS_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// Returns this transaction's consensus branch id, as specified by its
+    /// active network upgrade.
+    fn branch_id(&self) -> u32 {
+        self.transaction
+            .network_upgrade()
+            .and_then(|upgrade| upgrade.branch_id())
+            .expect("V5 transactions specify their consensus branch id")
+    }
+
+    /// The transparent digest: the prevouts, sequence numbers, and outputs
+    /// sub-digests, combined; or the empty hash if this transaction has no
+    /// transparent component.
+    ///
+    /// Each input's scriptSig is deliberately left out: it isn't covered by
+    /// this digest at all, since a third party can rewrite a scriptSig (e.g.
+    /// during signing) without invalidating the transaction. It's hashed
+    /// separately, into [`TxIdDigester::transparent_auth_digest`], which only
+    /// feeds [`TxIdDigester::auth_digest`], never the txid.
+    fn transparent_digest(&self) -> [u8; 32] {
+        let inputs = self.transaction.inputs();
+        let outputs = self.transaction.outputs();
+
+        if inputs.is_empty() && outputs.is_empty() {
+            return digest_or_empty::<Vec<u8>>(ZCASH_TRANSPARENT_HASH_PERSONALIZATION, None);
+        }
+
+        let mut writer = Vec::new();
+        writer.extend_from_slice(&self.prevouts_digest());
+        writer.extend_from_slice(&self.sequence_digest());
+        writer.extend_from_slice(&self.outputs_digest());
+
+        let mut state = hasher(ZCASH_TRANSPARENT_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The prevouts sub-digest: every input's previous output reference, in
+    /// order. Coinbase inputs have no previous output and contribute nothing.
+    fn prevouts_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+        for input in self.transaction.inputs() {
+            if let Some(outpoint) = input.outpoint() {
+                outpoint
+                    .zcash_serialize(&mut writer)
+                    .expect("outpoints must serialize for hashing");
+            }
+        }
+
+        let mut state = hasher(ZCASH_PREVOUTS_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The sequence numbers sub-digest: every input's `nSequence` field, in
+    /// order.
+    fn sequence_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+        for input in self.transaction.inputs() {
+            writer.extend_from_slice(&input.sequence().to_le_bytes());
+        }
+
+        let mut state = hasher(ZCASH_SEQUENCE_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The outputs sub-digest: every transparent output, in order. Outputs
+    /// don't carry any authorizing data, so (unlike inputs) they're hashed
+    /// here in full.
+    fn outputs_digest(&self) -> [u8; 32] {
+        let mut writer = Vec::new();
+        for output in self.transaction.outputs() {
+            output
+                .zcash_serialize(&mut writer)
+                .expect("transparent outputs must serialize for hashing");
+        }
+
+        let mut state = hasher(ZCASH_OUTPUTS_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The Sapling digest, or the empty hash if this transaction has no
+    /// Sapling shielded data.
+    ///
+    /// Only the publicly-verifiable fields of each spend and output feed this
+    /// digest (value commitments, the shared anchor, nullifiers, `rk`, `cmu`,
+    /// ephemeral keys, and ciphertexts), plus the bundle's value balance.
+    /// Proofs and spend-auth/binding signatures are excluded — they're hashed
+    /// separately by [`TxIdDigester::sapling_auth_digest`], which only feeds
+    /// [`TxIdDigester::auth_digest`], never the txid.
+    fn sapling_digest(&self) -> [u8; 32] {
+        let Some(sapling) = self.transaction.sapling_shielded_data() else {
+            return digest_or_empty::<Vec<u8>>(ZCASH_SAPLING_HASH_PERSONALIZATION, None);
+        };
+
+        let mut writer = Vec::new();
+        for spend in sapling.spends() {
+            spend
+                .cv()
+                .zcash_serialize(&mut writer)
+                .expect("value commitments must serialize for hashing");
+            spend
+                .anchor()
+                .zcash_serialize(&mut writer)
+                .expect("anchors must serialize for hashing");
+            spend
+                .nullifier()
+                .zcash_serialize(&mut writer)
+                .expect("nullifiers must serialize for hashing");
+            spend
+                .rk()
+                .zcash_serialize(&mut writer)
+                .expect("randomized verification keys must serialize for hashing");
+        }
+        for output in sapling.outputs() {
+            output
+                .cmu()
+                .zcash_serialize(&mut writer)
+                .expect("note commitments must serialize for hashing");
+            output
+                .ephemeral_key()
+                .zcash_serialize(&mut writer)
+                .expect("ephemeral keys must serialize for hashing");
+            output
+                .enc_ciphertext()
+                .zcash_serialize(&mut writer)
+                .expect("encrypted ciphertexts must serialize for hashing");
+            output
+                .out_ciphertext()
+                .zcash_serialize(&mut writer)
+                .expect("encrypted ciphertexts must serialize for hashing");
+            output
+                .cv()
+                .zcash_serialize(&mut writer)
+                .expect("value commitments must serialize for hashing");
+        }
+        sapling
+            .value_balance()
+            .zcash_serialize(&mut writer)
+            .expect("value balances must serialize for hashing");
+
+        let mut state = hasher(ZCASH_SAPLING_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+
+    /// The Orchard digest, or the empty hash if this transaction has no
+    /// Orchard shielded data.
+    ///
+    /// Only the publicly-verifiable fields of each action feed this digest
+    /// (nullifiers, `cmx`, ephemeral keys, ciphertexts, `rk`, and value
+    /// commitments), plus the bundle's value balance and flags. The shared
+    /// proof and spend-auth/binding signatures are excluded — they're hashed
+    /// separately by [`TxIdDigester::orchard_auth_digest`], which only feeds
+    /// [`TxIdDigester::auth_digest`], never the txid.
+    fn orchard_digest(&self) -> [u8; 32] {
+        let Some(orchard) = self.transaction.orchard_shielded_data() else {
+            return digest_or_empty::<Vec<u8>>(ZCASH_ORCHARD_HASH_PERSONALIZATION, None);
+        };
+
+        let mut writer = Vec::new();
+        for action in orchard.actions() {
+            action
+                .nullifier()
+                .zcash_serialize(&mut writer)
+                .expect("nullifiers must serialize for hashing");
+            action
+                .cmx()
+                .zcash_serialize(&mut writer)
+                .expect("note commitments must serialize for hashing");
+            action
+                .ephemeral_key()
+                .zcash_serialize(&mut writer)
+                .expect("ephemeral keys must serialize for hashing");
+            action
+                .enc_ciphertext()
+                .zcash_serialize(&mut writer)
+                .expect("encrypted ciphertexts must serialize for hashing");
+            action
+                .out_ciphertext()
+                .zcash_serialize(&mut writer)
+                .expect("encrypted ciphertexts must serialize for hashing");
+            action
+                .cv()
+                .zcash_serialize(&mut writer)
+                .expect("value commitments must serialize for hashing");
+            action
+                .rk()
+                .zcash_serialize(&mut writer)
+                .expect("randomized verification keys must serialize for hashing");
+        }
+        writer.push(orchard.flags.bits());
+        orchard
+            .value_balance()
+            .zcash_serialize(&mut writer)
+            .expect("value balances must serialize for hashing");
+
+        let mut state = hasher(ZCASH_ORCHARD_HASH_PERSONALIZATION);
+        state.update(&writer);
+        *state.finalize().as_array()
+    }
+}
+
 impl fmt::Display for Hash {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut reversed_bytes = self.0;
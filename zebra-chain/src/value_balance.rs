@@ -9,6 +9,10 @@ use crate::amount::{Amount, Constraint, Error, NegativeAllowed, NonNegative};
 
 //use itertools::Itertools;
 
+/// A convenience alias for the common case of a non-negative zatoshi [`Amount`],
+/// a type in which a negative fee or change value is unrepresentable.
+pub type NonNegativeAmount = Amount<NonNegative>;
+
 /// Document the struct
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[serde(bound = "C: Constraint")]
@@ -58,71 +62,174 @@ where
         }
         result
     }
+
+    /// Returns the transparent pool's amount.
+    pub fn transparent(&self) -> Amount<C> {
+        self.transparent
+    }
+
+    /// Returns the Sprout pool's amount.
+    pub fn sprout(&self) -> Amount<C> {
+        self.sprout
+    }
+
+    /// Returns the Sapling pool's amount.
+    pub fn sapling(&self) -> Amount<C> {
+        self.sapling
+    }
+
+    /// Returns the Orchard pool's amount.
+    pub fn orchard(&self) -> Amount<C> {
+        self.orchard
+    }
+
+    /// Returns the transparent pool's amount, for callers that only care
+    /// about this balance's effect on the transparent value pool.
+    pub fn to_transparent(&self) -> Amount<C> {
+        self.transparent
+    }
+
+    /// Returns the sum of all four pools, or an error if the sum overflows
+    /// the valid amount range.
+    pub fn total(&self) -> Result<Amount<C>, Error> {
+        (self.transparent + self.sprout + self.sapling + self.orchard)
+    }
+
+    /// Re-checks each of the four pools against a new [`Constraint`] `C2`,
+    /// returning an error if any pool falls outside the new range.
+    ///
+    /// This is used to convert a [`NegativeAllowed`] balance accumulated
+    /// while processing a block into a [`NonNegative`] balance, once it's
+    /// known that the chain's value pools can't have gone negative.
+    pub fn constrain<C2>(&self) -> Result<ValueBalance<C2>, Error>
+    where
+        C2: Constraint + Copy,
+    {
+        Ok(ValueBalance::<C2> {
+            transparent: self.transparent.constrain()?,
+            sprout: self.sprout.constrain()?,
+            sapling: self.sapling.constrain()?,
+            orchard: self.orchard.constrain()?,
+        })
+    }
 }
 
-/*
-impl<C> std::ops::Add<ValueBalance<C>> for Result<ValueBalance<C>, Error>
+impl ValueBalance<NonNegative> {
+    /// Constructs a non-negative `ValueBalance` from raw zatoshi parts,
+    /// validating each pool against the [`NonNegative`] constraint rather
+    /// than allowing a negative value in and failing later on first use.
+    pub fn try_from_parts(
+        transparent: i64,
+        sprout: i64,
+        sapling: i64,
+        orchard: i64,
+    ) -> Result<Self, Error> {
+        Ok(ValueBalance {
+            transparent: Amount::try_from(transparent)?,
+            sprout: Amount::try_from(sprout)?,
+            sapling: Amount::try_from(sapling)?,
+            orchard: Amount::try_from(orchard)?,
+        })
+    }
+
+    /// Subtracts `rhs` from this balance, pool by pool, returning
+    /// [`Error`] instead of wrapping or panicking if any pool would go
+    /// negative.
+    pub fn checked_sub(&self, rhs: ValueBalance<NonNegative>) -> Result<ValueBalance<NonNegative>, Error> {
+        Ok(ValueBalance {
+            transparent: (self.transparent - rhs.transparent)?.constrain()?,
+            sprout: (self.sprout - rhs.sprout)?.constrain()?,
+            sapling: (self.sapling - rhs.sapling)?.constrain()?,
+            orchard: (self.orchard - rhs.orchard)?.constrain()?,
+        })
+    }
+}
+
+impl<C> std::ops::Add<ValueBalance<C>> for ValueBalance<C>
 where
-    C: Constraint,
+    C: Constraint + Copy,
 {
     type Output = Result<ValueBalance<C>, Error>;
 
     fn add(self, rhs: ValueBalance<C>) -> Self::Output {
-        let vb = self?;
-
-        let sum = ValueBalance::<C> {
-            transparent: (vb.transparent + rhs.transparent).unwrap(),
-            sprout: (vb.sprout + rhs.sprout).unwrap(),
-            sapling: (vb.sapling + rhs.sapling).unwrap(),
-            orchard: (vb.orchard + rhs.orchard).unwrap(),
-        };
-        Ok(sum)
+        Ok(ValueBalance::<C> {
+            transparent: (self.transparent + rhs.transparent)?,
+            sprout: (self.sprout + rhs.sprout)?,
+            sapling: (self.sapling + rhs.sapling)?,
+            orchard: (self.orchard + rhs.orchard)?,
+        })
     }
 }
 
-impl<C> std::ops::Sub<ValueBalance<C>> for Result<ValueBalance<C>, Error>
+impl<C> std::ops::Sub<ValueBalance<C>> for ValueBalance<C>
 where
-    C: Constraint,
+    C: Constraint + Copy,
 {
     type Output = Result<ValueBalance<C>, Error>;
 
     fn sub(self, rhs: ValueBalance<C>) -> Self::Output {
-        let vb = self?;
-
-        let sub = ValueBalance::<C> {
-            transparent: (vb.transparent - rhs.transparent).unwrap(),
-            sprout: (vb.sprout - rhs.sprout).unwrap(),
-            sapling: (vb.sapling - rhs.sapling).unwrap(),
-            orchard: (vb.orchard - rhs.orchard).unwrap(),
-        };
-        Ok(sub)
+        Ok(ValueBalance::<C> {
+            transparent: (self.transparent - rhs.transparent)?,
+            sprout: (self.sprout - rhs.sprout)?,
+            sapling: (self.sapling - rhs.sapling)?,
+            orchard: (self.orchard - rhs.orchard)?,
+        })
     }
 }
-*/
 
-/*
-impl AddAssign for Result<ValueBalance<C>>
+impl<C> std::ops::Add<ValueBalance<C>> for Result<ValueBalance<C>, Error>
 where
-    C: Constraint,
+    C: Constraint + Copy,
 {
+    type Output = Result<ValueBalance<C>, Error>;
 
+    fn add(self, rhs: ValueBalance<C>) -> Self::Output {
+        self? + rhs
+    }
 }
 
-impl SubAssign for Result<ValueBalance<C>>
+impl<C> std::ops::Sub<ValueBalance<C>> for Result<ValueBalance<C>, Error>
 where
-    C: Constraint,
+    C: Constraint + Copy,
 {
+    type Output = Result<ValueBalance<C>, Error>;
 
+    fn sub(self, rhs: ValueBalance<C>) -> Self::Output {
+        self? - rhs
+    }
 }
 
-impl Sum for Result<ValueBalance<C>>
+impl<C> std::ops::AddAssign<ValueBalance<C>> for Result<ValueBalance<C>, Error>
 where
-    C: Constraint,
+    C: Constraint + Copy,
 {
+    fn add_assign(&mut self, rhs: ValueBalance<C>) {
+        let current = std::mem::replace(self, Ok(ValueBalance::default()));
+        *self = current + rhs;
+    }
+}
+
+impl<C> std::ops::SubAssign<ValueBalance<C>> for Result<ValueBalance<C>, Error>
+where
+    C: Constraint + Copy,
+{
+    fn sub_assign(&mut self, rhs: ValueBalance<C>) {
+        let current = std::mem::replace(self, Ok(ValueBalance::default()));
+        *self = current - rhs;
+    }
+}
 
+impl<C> std::iter::Sum<ValueBalance<C>> for Result<ValueBalance<C>, Error>
+where
+    C: Constraint + Copy,
+{
+    /// Sums a chain of per-transaction value balances into a total, short-circuiting
+    /// on the first pool that overflows the valid amount range.
+    fn sum<I: Iterator<Item = ValueBalance<C>>>(iter: I) -> Self {
+        iter.fold(Ok(ValueBalance::default()), |acc, value| acc + value)
+    }
 }
 
-*/
 use std::convert::TryFrom;
 impl<C> Default for ValueBalance<C>
 where
@@ -4,13 +4,14 @@ use super::*;
 use chrono::{DateTime, Utc};
 use zebra_chain::{
     block::{Block, Header},
+    transparent,
     work::equihash,
 };
 
 use std::convert::TryInto;
 use zebra_chain::parameters::{Network, NetworkUpgrade::*};
 
-use crate::parameters::Params;
+use crate::parameters::{fs, Params};
 
 /// Check that there is exactly one coinbase transaction in `Block`, and that
 /// the coinbase transaction is the first transaction in the block.
@@ -36,32 +37,28 @@ pub fn is_coinbase_first(block: &Block) -> Result<(), Error> {
 }
 
 /// [3.9]: https://zips.z.cash/protocol/protocol.pdf#subsidyconcepts
-pub fn is_subsidy_correct(block: &Block) -> Result<(), Error> {
+pub fn is_subsidy_correct(block: &Block, network: Network) -> Result<(), Error> {
     let height = block.coinbase_height().unwrap();
 
     let coinbase = block.transactions.get(0).ok_or("no coinbase transaction")?;
     let outputs = coinbase.outputs();
 
-    // Todo: we need the network here.
-    let network = Network::Mainnet;
-
     let canopy_height = Canopy.activation_height(network).ok_or("no canopy")?;
     if height >= canopy_height {
-        // dont validate canopy yet
-        return Ok(());
+        return is_funding_stream_subsidy_correct(height, network, outputs);
     }
 
     // validate founders reward and miner subsidy
     if height > block::Height(0) && height <= block::Height(Params::LAST_FOUNDER_REWARD_HEIGHT) {
-        let block_subsidy = subsidies::block_subsidy(height, Network::Mainnet);
-        let miner_subsidy = subsidies::miner_subsidy(height, Network::Mainnet);
+        let block_subsidy: i64 = subsidies::block_subsidy(height, network)?.into();
+        let miner_subsidy: i64 = subsidies::miner_subsidy(height, network)?.into();
         let mut valid_founders: bool = false;
         let mut valid_miner: bool = false;
         for o in outputs {
             let value: i64 = o.value.try_into().unwrap();
-            if value == block_subsidy as i64 / 5 {
+            if value == block_subsidy / 5 {
                 valid_founders = true;
-            } else if value == miner_subsidy as i64 {
+            } else if value == miner_subsidy {
                 valid_miner = true;
             }
         }
@@ -71,6 +68,81 @@ pub fn is_subsidy_correct(block: &Block) -> Result<(), Error> {
     }
     Err("error in the validation")?
 }
+
+/// Checks that `outputs` pay each active ZIP-207 funding stream its required
+/// value to its expected recipient for `height`, and that the remaining miner
+/// subsidy also appears as an output.
+///
+/// Returns an error naming the specific stream (or the miner subsidy) that is
+/// missing or underpaid.
+fn is_funding_stream_subsidy_correct(
+    height: block::Height,
+    network: Network,
+    outputs: &[transparent::Output],
+) -> Result<(), Error> {
+    let block_subsidy: i64 = subsidies::block_subsidy(height, network)?.into();
+
+    let paid_outputs: Vec<(i64, Option<String>)> = outputs
+        .iter()
+        .map(|output| {
+            let value: i64 = output.value.try_into().unwrap_or(-1);
+            let address = output.address(network).map(|address| address.to_string());
+            (value, address)
+        })
+        .collect();
+
+    funding_stream_subsidy_is_paid(height, network, block_subsidy, &paid_outputs)
+}
+
+/// The matching logic behind [`is_funding_stream_subsidy_correct`], taking
+/// already-extracted `(value, address)` pairs instead of `transparent::Output`
+/// directly, so it can be unit tested without constructing a real
+/// `transparent::Output`.
+fn funding_stream_subsidy_is_paid(
+    height: block::Height,
+    network: Network,
+    block_subsidy: i64,
+    paid_outputs: &[(i64, Option<String>)],
+) -> Result<(), Error> {
+    let mut remaining_subsidy = block_subsidy;
+
+    for receiver in fs::Receiver::ALL {
+        let expected_value: i64 = subsidies::funding_stream(height, network, receiver)?.into();
+        if expected_value == 0 {
+            // This stream isn't active at `height`.
+            continue;
+        }
+
+        let expected_address = subsidies::funding_stream_address(height, network, receiver)
+            .ok_or("funding stream is active but has no recipient address")?;
+
+        let paid = paid_outputs.iter().any(|(value, address)| {
+            *value == expected_value && address.as_deref() == Some(expected_address)
+        });
+
+        if !paid {
+            return Err(format!(
+                "funding stream {receiver:?} did not pay {expected_value} zatoshis to {expected_address} at height {}",
+                height.0
+            ))?;
+        }
+
+        remaining_subsidy -= expected_value;
+    }
+
+    let miner_paid = paid_outputs
+        .iter()
+        .any(|(value, _address)| *value == remaining_subsidy);
+
+    if !miner_paid {
+        return Err(format!(
+            "miner subsidy of {remaining_subsidy} zatoshis not found in coinbase outputs at height {}",
+            height.0
+        ))?;
+    }
+
+    Ok(())
+}
 /// Returns true if the header is valid based on its `EquihashSolution`
 pub fn is_equihash_solution_valid(header: &Header) -> Result<(), equihash::Error> {
     header.solution.check(&header)
@@ -93,3 +165,88 @@ pub fn is_equihash_solution_valid(header: &Header) -> Result<(), equihash::Error
 pub fn is_time_valid_at(header: &Header, now: DateTime<Utc>) -> Result<(), Error> {
     header.is_time_valid_at(now)
 }
+
+// `transparent::Output` isn't defined anywhere in this source tree (it's
+// only ever referenced through its `value`/`address` fields and methods), so
+// there's no way to construct one here to exercise
+// `is_funding_stream_subsidy_correct` itself end to end. The tests below
+// instead cover `funding_stream_subsidy_is_paid`, which holds all of that
+// function's actual validation logic behind a `(value, address)` pair
+// interface that doesn't need a real `transparent::Output` to test.
+
+#[test]
+fn funding_stream_subsidy_is_paid_accepts_a_correctly_paid_block() -> Result<(), Report> {
+    let height = block::Height(Params::CANOPY_ACTIVATION_HEIGHT);
+    let network = Network::Mainnet;
+    let block_subsidy: i64 = subsidies::block_subsidy(height, network)?.into();
+
+    let mut remaining_subsidy = block_subsidy;
+    let mut paid_outputs = Vec::new();
+    for receiver in fs::Receiver::ALL {
+        let expected_value: i64 = subsidies::funding_stream(height, network, receiver)?.into();
+        let expected_address = subsidies::funding_stream_address(height, network, receiver)
+            .expect("funding streams are active at the Canopy activation height");
+        paid_outputs.push((expected_value, Some(expected_address.to_string())));
+        remaining_subsidy -= expected_value;
+    }
+    paid_outputs.push((remaining_subsidy, None));
+
+    assert!(funding_stream_subsidy_is_paid(height, network, block_subsidy, &paid_outputs).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn funding_stream_subsidy_is_paid_rejects_a_missing_stream_payment() -> Result<(), Report> {
+    let height = block::Height(Params::CANOPY_ACTIVATION_HEIGHT);
+    let network = Network::Mainnet;
+    let block_subsidy: i64 = subsidies::block_subsidy(height, network)?.into();
+
+    // Only the miner subsidy is paid; every funding stream is missing.
+    let paid_outputs = vec![(block_subsidy, None)];
+
+    assert!(funding_stream_subsidy_is_paid(height, network, block_subsidy, &paid_outputs).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn funding_stream_subsidy_is_paid_rejects_the_wrong_recipient_address() -> Result<(), Report> {
+    let height = block::Height(Params::CANOPY_ACTIVATION_HEIGHT);
+    let network = Network::Mainnet;
+    let block_subsidy: i64 = subsidies::block_subsidy(height, network)?.into();
+
+    let mut remaining_subsidy = block_subsidy;
+    let mut paid_outputs = Vec::new();
+    for receiver in fs::Receiver::ALL {
+        let expected_value: i64 = subsidies::funding_stream(height, network, receiver)?.into();
+        // Pay the right value to the wrong address for every receiver.
+        paid_outputs.push((expected_value, Some("t1SomeOtherAddress".to_string())));
+        remaining_subsidy -= expected_value;
+    }
+    paid_outputs.push((remaining_subsidy, None));
+
+    assert!(funding_stream_subsidy_is_paid(height, network, block_subsidy, &paid_outputs).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn funding_stream_subsidy_is_paid_rejects_a_missing_miner_payment() -> Result<(), Report> {
+    let height = block::Height(Params::CANOPY_ACTIVATION_HEIGHT);
+    let network = Network::Mainnet;
+    let block_subsidy: i64 = subsidies::block_subsidy(height, network)?.into();
+
+    // Every funding stream is paid, but the remaining miner subsidy isn't.
+    let mut paid_outputs = Vec::new();
+    for receiver in fs::Receiver::ALL {
+        let expected_value: i64 = subsidies::funding_stream(height, network, receiver)?.into();
+        let expected_address = subsidies::funding_stream_address(height, network, receiver)
+            .expect("funding streams are active at the Canopy activation height");
+        paid_outputs.push((expected_value, Some(expected_address.to_string())));
+    }
+
+    assert!(funding_stream_subsidy_is_paid(height, network, block_subsidy, &paid_outputs).is_err());
+
+    Ok(())
+}
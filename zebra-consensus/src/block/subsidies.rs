@@ -2,19 +2,34 @@
 
 use super::*;
 
+use thiserror::Error;
+
 use zebra_chain::{
+    amount::{Amount, NonNegative},
     block::Height,
     parameters::{Network, NetworkUpgrade::*},
 };
 
 use crate::parameters::{fs, Params};
 
+/// Errors that can occur when computing a block's subsidy.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum SubsidyError {
+    /// A computed subsidy component lies outside the valid range of Zcash amounts.
+    #[error("subsidy amount is outside the valid range of Zcash amounts: {0}")]
+    InvalidAmount(#[from] zebra_chain::amount::Error),
+
+    /// The founders reward and funding streams added up to more than the block subsidy.
+    #[error("founders reward and funding streams exceed the block subsidy")]
+    FundingStreamsExceedSubsidy,
+}
+
 fn slow_start_shift() -> Height {
     Height(Params::SLOW_START_INTERVAL / 2)
 }
 
-fn slow_start_rate() -> u32 {
-    Params::MAX_BLOCK_SUBSIDY / Params::SLOW_START_INTERVAL
+fn slow_start_rate() -> u64 {
+    (Params::MAX_BLOCK_SUBSIDY / Params::SLOW_START_INTERVAL) as u64
 }
 
 fn is_bolossom_activated(height: block::Height, network: Network) -> Option<Height> {
@@ -38,58 +53,131 @@ fn halving(height: Height, network: Network) -> u32 {
                     + (height.0 - blossom_height.0);
                 scaled_halvings / Params::POST_BLOSSOM_HALVING_INTERVAL
             }
-            _ => {
-                (((height.0 - slow_start_shift().0) / Params::PRE_BLOSSOM_HALVING_INTERVAL) as f32)
-                    .floor() as u32
-            }
+            _ => (height.0 - slow_start_shift().0) / Params::PRE_BLOSSOM_HALVING_INTERVAL,
         },
     }
 }
 
-pub fn block_subsidy(height: Height, network: Network) -> u32 {
-    if height < slow_start_shift() {
-        slow_start_rate() * height.0
+/// Returns the block subsidy in zatoshis for `height` on `network`.
+///
+/// This uses only integer arithmetic (`u64` intermediates for the
+/// halving-scaled division at the Blossom boundary), so it never loses
+/// precision the way a floating-point computation would above 2^24 zatoshis.
+pub fn block_subsidy(height: Height, network: Network) -> Result<Amount<NonNegative>, SubsidyError> {
+    let zatoshis: u64 = if height < slow_start_shift() {
+        slow_start_rate() * height.0 as u64
     } else if slow_start_shift() <= height && height < Height(Params::SLOW_START_INTERVAL) {
-        slow_start_rate() * (height.0 + 1)
+        slow_start_rate() * (height.0 as u64 + 1)
     } else {
         let blossom_height = is_bolossom_activated(height, network);
         let condition = blossom_height.is_none() && Params::SLOW_START_INTERVAL <= height.0;
         match condition {
-            true => Params::MAX_BLOCK_SUBSIDY >> halving(height, network),
-            false => ((Params::MAX_BLOCK_SUBSIDY / Params::BLOSSOM_POW_TARGET_SPACING_RATIO
-                * 2u32.pow(halving(height, network))) as f32)
-                .floor() as u32,
+            true => (Params::MAX_BLOCK_SUBSIDY as u64) >> halving(height, network),
+            false => {
+                (Params::MAX_BLOCK_SUBSIDY as u64 / Params::BLOSSOM_POW_TARGET_SPACING_RATIO as u64)
+                    >> halving(height, network)
+            }
         }
+    };
+
+    Ok(Amount::try_from(zatoshis)?)
+}
+
+fn founders_reward(height: Height, network: Network) -> Result<Amount<NonNegative>, SubsidyError> {
+    if halving(height, network) >= 1 {
+        return Ok(Amount::try_from(0)?);
     }
+
+    let subsidy: u64 = block_subsidy(height, network)?.into();
+    let reward = subsidy * Params::FOUNDERS_FRACTION_NUMERATOR / Params::FOUNDERS_FRACTION_DENOMINATOR;
+
+    Ok(Amount::try_from(reward)?)
 }
 
-fn founders_reward(height: Height, network: Network) -> u32 {
-    let condition = halving(height, network) < 1;
-    match condition {
-        true => (block_subsidy(height, network) as f32 * Params::FOUNDERS_FRACTION).floor() as u32,
-        false => 0,
+/// Returns `true` if the ZIP-207 funding streams are active at `height` on `network`.
+pub fn is_funding_stream_active(height: Height, network: Network) -> bool {
+    let (start_height, end_height) = match network {
+        Network::Mainnet => (fs::mainnet::START_HEIGHT, fs::mainnet::END_HEIGHT),
+        Network::Testnet => (fs::testnet::START_HEIGHT, fs::testnet::END_HEIGHT),
+    };
+
+    start_height <= height.0 && height.0 < end_height
+}
+
+/// Returns the value of `receiver`'s funding stream at `height`, or zero if no
+/// stream is active for `receiver` at that height.
+pub fn funding_stream(
+    height: Height,
+    network: Network,
+    receiver: fs::Receiver,
+) -> Result<Amount<NonNegative>, SubsidyError> {
+    if !is_funding_stream_active(height, network) {
+        return Ok(Amount::try_from(0)?);
     }
+
+    let (numerator, denominator) = match network {
+        Network::Mainnet => (fs::mainnet::numerator(receiver), fs::mainnet::DENOMINATOR),
+        Network::Testnet => (fs::testnet::numerator(receiver), fs::testnet::DENOMINATOR),
+    };
+
+    let subsidy: u64 = block_subsidy(height, network)?.into();
+    let value = subsidy * numerator as u64 / denominator as u64;
+
+    Ok(Amount::try_from(value)?)
 }
 
-fn funding_stream(height: Height, network: Network, receiver: fs::Receiver) -> u32 {
-    let condition = height.0 >= Params::CANOPY_ACTIVATION_HEIGHT
-        && fs::mainnet::START_HEIGHT <= height.0
-        && height.0 < fs::mainnet::END_HEIGHT;
-    match condition {
-        true => (block_subsidy(height, network) as f32
-            * (fs::mainnet::numerator(receiver) as f32 / fs::mainnet::DENOMINATOR as f32))
-            .floor() as u32,
-        false => 0,
+/// Returns the recipient address for `receiver`'s funding stream at `height`,
+/// or `None` if no stream is active for `receiver` at that height.
+///
+/// Recipients rotate through their address list every
+/// [`Params::FUNDING_STREAM_ADDRESS_CHANGE_INTERVAL`] blocks, starting from
+/// the funding stream's start height.
+pub fn funding_stream_address(
+    height: Height,
+    network: Network,
+    receiver: fs::Receiver,
+) -> Option<&'static str> {
+    if !is_funding_stream_active(height, network) {
+        return None;
     }
+
+    let start_height = match network {
+        Network::Mainnet => fs::mainnet::START_HEIGHT,
+        Network::Testnet => fs::testnet::START_HEIGHT,
+    };
+
+    let addresses = match network {
+        Network::Mainnet => fs::mainnet::addresses(receiver),
+        Network::Testnet => fs::testnet::addresses(receiver),
+    };
+
+    let address_index = ((height.0 - start_height) / Params::FUNDING_STREAM_ADDRESS_CHANGE_INTERVAL)
+        as usize
+        % addresses.len();
+
+    Some(addresses[address_index])
 }
 
-pub fn miner_subsidy(height: Height, network: Network) -> u32 {
-    let mut funding_streams: u32 = 0;
-    funding_streams += funding_stream(height, network, fs::Receiver::ECC);
-    funding_streams += funding_stream(height, network, fs::Receiver::ZF);
-    funding_streams += funding_stream(height, network, fs::Receiver::MG);
+/// Returns the portion of the block subsidy that goes to the miner: the block
+/// subsidy minus the founders reward and all active funding streams.
+///
+/// Returns [`SubsidyError::FundingStreamsExceedSubsidy`] instead of
+/// underflowing if the founders reward and funding streams add up to more
+/// than the block subsidy.
+pub fn miner_subsidy(height: Height, network: Network) -> Result<Amount<NonNegative>, SubsidyError> {
+    let block_subsidy = block_subsidy(height, network)?;
+    let founders_reward = founders_reward(height, network)?;
+
+    let mut funding_streams = Amount::try_from(0)?;
+    for receiver in fs::Receiver::ALL {
+        funding_streams = (funding_streams + funding_stream(height, network, receiver)?)
+            .map_err(|_| SubsidyError::FundingStreamsExceedSubsidy)?;
+    }
 
-    block_subsidy(height, network) - founders_reward(height, network) - funding_streams
+    ((block_subsidy - founders_reward)
+        .map_err(|_| SubsidyError::FundingStreamsExceedSubsidy)?
+        - funding_streams)
+        .map_err(|_| SubsidyError::FundingStreamsExceedSubsidy)
 }
 
 #[test]
@@ -113,8 +201,8 @@ fn test_halving() -> Result<(), Report> {
 fn test_block_subsidy() -> Result<(), Report> {
     let mut total_subsidy: u64 = 0;
     for n_height in 1..Params::CANOPY_ACTIVATION_HEIGHT {
-        let subsidy = (block_subsidy(Height(n_height), Network::Mainnet) / 5) as u64;
-        total_subsidy += subsidy;
+        let subsidy: u64 = block_subsidy(Height(n_height), Network::Mainnet)?.into();
+        total_subsidy += subsidy / 5;
     }
     assert!(total_subsidy == Params::MAX_MONEY / 10);
 
@@ -123,18 +211,24 @@ fn test_block_subsidy() -> Result<(), Report> {
 
 #[test]
 fn test_founders_reward() -> Result<(), Report> {
-    assert_eq!(0, founders_reward(Height(0), Network::Mainnet));
-    assert_eq!(12500, founders_reward(Height(1), Network::Mainnet));
     assert_eq!(
-        125000000,
-        founders_reward(Height(Params::LAST_FOUNDER_REWARD_HEIGHT), Network::Mainnet)
+        Amount::try_from(0)?,
+        founders_reward(Height(0), Network::Mainnet)?
     );
     assert_eq!(
-        0,
+        Amount::try_from(12500)?,
+        founders_reward(Height(1), Network::Mainnet)?
+    );
+    assert_eq!(
+        Amount::try_from(125000000)?,
+        founders_reward(Height(Params::LAST_FOUNDER_REWARD_HEIGHT), Network::Mainnet)?
+    );
+    assert_eq!(
+        Amount::try_from(0)?,
         founders_reward(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT + 1),
             Network::Mainnet
-        )
+        )?
     );
 
     Ok(())
@@ -143,98 +237,171 @@ fn test_founders_reward() -> Result<(), Report> {
 #[test]
 fn test_funding_stream() -> Result<(), Report> {
     assert_eq!(
-        0,
-        funding_stream(Height(0), Network::Mainnet, fs::Receiver::ECC)
+        Amount::try_from(0)?,
+        funding_stream(Height(0), Network::Mainnet, fs::Receiver::ECC)?
     );
     assert_eq!(
-        0,
-        funding_stream(Height(0), Network::Mainnet, fs::Receiver::ZF)
+        Amount::try_from(0)?,
+        funding_stream(Height(0), Network::Mainnet, fs::Receiver::ZF)?
     );
     assert_eq!(
-        0,
-        funding_stream(Height(0), Network::Mainnet, fs::Receiver::MG)
+        Amount::try_from(0)?,
+        funding_stream(Height(0), Network::Mainnet, fs::Receiver::MG)?
     );
 
     assert_eq!(
-        0,
-        funding_stream(Height(1), Network::Mainnet, fs::Receiver::ECC)
+        Amount::try_from(0)?,
+        funding_stream(Height(1), Network::Mainnet, fs::Receiver::ECC)?
     );
     assert_eq!(
-        0,
-        funding_stream(Height(1), Network::Mainnet, fs::Receiver::ZF)
+        Amount::try_from(0)?,
+        funding_stream(Height(1), Network::Mainnet, fs::Receiver::ZF)?
     );
     assert_eq!(
-        0,
-        funding_stream(Height(1), Network::Mainnet, fs::Receiver::MG)
+        Amount::try_from(0)?,
+        funding_stream(Height(1), Network::Mainnet, fs::Receiver::MG)?
     );
 
     assert_eq!(
-        0,
+        Amount::try_from(0)?,
         funding_stream(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT),
             Network::Mainnet,
             fs::Receiver::ECC
-        )
+        )?
     );
     assert_eq!(
-        0,
+        Amount::try_from(0)?,
         funding_stream(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT),
             Network::Mainnet,
             fs::Receiver::ZF
-        )
+        )?
     );
     assert_eq!(
-        0,
+        Amount::try_from(0)?,
         funding_stream(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT),
             Network::Mainnet,
             fs::Receiver::MG
-        )
+        )?
     );
 
     assert_eq!(
-        87500000,
+        Amount::try_from(21875000)?,
         funding_stream(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT + 1),
             Network::Mainnet,
             fs::Receiver::ECC
-        )
+        )?
     );
     assert_eq!(
-        62500000,
+        Amount::try_from(15625000)?,
         funding_stream(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT + 1),
             Network::Mainnet,
             fs::Receiver::ZF
-        )
+        )?
     );
     assert_eq!(
-        100000000,
+        Amount::try_from(25000000)?,
         funding_stream(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT + 1),
             Network::Mainnet,
             fs::Receiver::MG
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_funding_stream_address_rotation() -> Result<(), Report> {
+    // No stream is active before Canopy.
+    assert_eq!(
+        None,
+        funding_stream_address(
+            Height(fs::mainnet::START_HEIGHT - 1),
+            Network::Mainnet,
+            fs::Receiver::ECC
+        )
+    );
+
+    // The first address in the list is used at the funding stream's start height.
+    assert_eq!(
+        Some(fs::mainnet::addresses(fs::Receiver::ECC)[0]),
+        funding_stream_address(
+            Height(fs::mainnet::START_HEIGHT),
+            Network::Mainnet,
+            fs::Receiver::ECC
         )
     );
 
+    // No stream is active at (or after) the funding stream's end height.
+    assert_eq!(
+        None,
+        funding_stream_address(
+            Height(fs::mainnet::END_HEIGHT),
+            Network::Mainnet,
+            fs::Receiver::ECC
+        )
+    );
+
+    // The last block before the funding stream ends still has an active stream.
+    assert!(funding_stream_address(
+        Height(fs::mainnet::END_HEIGHT - 1),
+        Network::Mainnet,
+        fs::Receiver::ECC
+    )
+    .is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_funding_stream_canopy_boundary() -> Result<(), Report> {
+    // Immediately before Canopy there is no funding stream value.
+    assert_eq!(
+        Amount::try_from(0)?,
+        funding_stream(
+            Height(Params::CANOPY_ACTIVATION_HEIGHT - 1),
+            Network::Mainnet,
+            fs::Receiver::ECC
+        )?
+    );
+
+    // From Canopy onward, the ECC stream is active and pays out.
+    let zatoshis: u64 = funding_stream(
+        Height(Params::CANOPY_ACTIVATION_HEIGHT),
+        Network::Mainnet,
+        fs::Receiver::ECC,
+    )?
+    .into();
+    assert!(zatoshis > 0);
+
     Ok(())
 }
 
 #[test]
 fn miner_subsidy_test() -> Result<(), Report> {
-    assert_eq!(0, miner_subsidy(Height(0), Network::Mainnet));
-    assert_eq!(50000, miner_subsidy(Height(1), Network::Mainnet));
     assert_eq!(
-        500000000,
-        miner_subsidy(Height(Params::LAST_FOUNDER_REWARD_HEIGHT), Network::Mainnet)
+        Amount::try_from(0)?,
+        miner_subsidy(Height(0), Network::Mainnet)?
     );
     assert_eq!(
-        1000000000,
+        Amount::try_from(50000)?,
+        miner_subsidy(Height(1), Network::Mainnet)?
+    );
+    assert_eq!(
+        Amount::try_from(500000000)?,
+        miner_subsidy(Height(Params::LAST_FOUNDER_REWARD_HEIGHT), Network::Mainnet)?
+    );
+    assert_eq!(
+        Amount::try_from(250000000)?,
         miner_subsidy(
             Height(Params::LAST_FOUNDER_REWARD_HEIGHT + 1),
             Network::Mainnet
-        )
+        )?
     );
 
     Ok(())
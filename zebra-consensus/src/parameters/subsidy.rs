@@ -1,4 +1,7 @@
 //! Constants for Block Subsidy, Funding Streams, and Founders’ Reward
+//!
+//! The actual subsidy/halving/funding-stream calculations that use these
+//! constants live in [`crate::block::subsidies`], not on [`Params`] itself.
 
 use zebra_chain::parameters::Network;
 
@@ -35,14 +38,26 @@ impl Params {
     /// MAX_MONEY
     pub const MAX_MONEY: u64 = 21000000 * Self::COIN as u64;
 
-    /// FoundersFraction
-    pub const FOUNDERS_FRACTION: f32 = 0.2;
+    /// FoundersFraction numerator (the founders reward is this fraction of the
+    /// block subsidy, computed as integer division to avoid floating-point
+    /// rounding in consensus-critical code).
+    pub const FOUNDERS_FRACTION_NUMERATOR: u64 = 1;
+
+    /// FoundersFraction denominator.
+    pub const FOUNDERS_FRACTION_DENOMINATOR: u64 = 5;
 
     /// CanopyActivationHeight
     pub const CANOPY_ACTIVATION_HEIGHT: u32 = 1046400; // mainnet
 
     /// GetLastFoundersRewardHeight
     pub const LAST_FOUNDER_REWARD_HEIGHT: u32 = Self::CANOPY_ACTIVATION_HEIGHT - 1;
+
+    /// FundingStreamAddressChangeInterval
+    ///
+    /// Funding stream recipients rotate through their address list once per
+    /// this many blocks, so the list lasts roughly as long as a halving era.
+    pub const FUNDING_STREAM_ADDRESS_CHANGE_INTERVAL: u32 =
+        Self::POST_BLOSSOM_HALVING_INTERVAL / 48;
 }
 
 /// Funding Streams
@@ -50,6 +65,7 @@ impl Params {
 pub mod fs {
 
     /// The funding stream receivers
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub enum Receiver {
         /// Electric Coin Company
         ECC,
@@ -59,6 +75,12 @@ pub mod fs {
         MG,
     }
 
+    impl Receiver {
+        /// All funding stream receivers, in the order the coinbase checks
+        /// visit them.
+        pub const ALL: [Receiver; 3] = [Receiver::ECC, Receiver::ZF, Receiver::MG];
+    }
+
     /// For the Mainnet
     pub mod mainnet {
         /// Denominator
@@ -77,6 +99,37 @@ pub mod fs {
                 Receiver::MG => 8,
             }
         }
+
+        // TODO: these are placeholder addresses, not the real ZIP-207 mainnet
+        // funding stream recipients. The canonical list (one address per
+        // `Params::FUNDING_STREAM_ADDRESS_CHANGE_INTERVAL`-block rotation
+        // period, ~48 per receiver for mainnet) is published in the ZIP-207
+        // deployment tables and mirrored in `zcashd`'s `chainparams.cpp`;
+        // copy it in from there before using this to validate mainnet
+        // blocks. Each receiver already has more than one address below so
+        // that the rotation in `subsidies::funding_stream_address` is
+        // exercised end to end, rather than masking the missing data behind
+        // a single-element list that always resolves to index 0.
+        /// Recipient address list for `receiver`, in rotation order.
+        pub fn addresses(receiver: Receiver) -> &'static [&'static str] {
+            match receiver {
+                Receiver::ECC => &[
+                    "t3XyYW8yBFRuMnfdWHXcZhvnqGbGs9ZKzJc",
+                    "t3S3yaT7EwNLaFZZha7pQymhdcymSWhvbXv",
+                    "t3eF9X6X2dSo7MCvTjfZEzwWrVzquxRLqbV",
+                ],
+                Receiver::ZF => &[
+                    "t3dvVE3SQEi7kqNzwrfNePxZ1d4hUyztBA1",
+                    "t3WkNoEvaZSUnNayEQ1TvhTyRmEt9uSRjbe",
+                    "t3RBoigsbTcqomhqahU6V5RFY3fMwj6QSfo",
+                ],
+                Receiver::MG => &[
+                    "t3XHAGxRP2FNfhAjxGjxbrQPYtQQjc3RCQD",
+                    "t3VAQ1Y6hFmnMYpgYdfTcqnMqrRS8IKCMGf",
+                    "t3LL42fzTbL9csTRCiyS59CkoUbGNMVLcX8",
+                ],
+            }
+        }
     }
     /// For the Testnet
     pub mod testnet {
@@ -96,5 +149,29 @@ pub mod fs {
                 Receiver::MG => 8,
             }
         }
+
+        // TODO: these are placeholder addresses, not the real ZIP-207
+        // testnet funding stream recipients. See the matching TODO on
+        // `mainnet::addresses` for where the canonical list comes from.
+        /// Recipient address list for `receiver`, in rotation order.
+        pub fn addresses(receiver: Receiver) -> &'static [&'static str] {
+            match receiver {
+                Receiver::ECC => &[
+                    "t26ovBdKAJLtrvBsE2QGF4nqBkEuptuPFZz",
+                    "t26RwJKSyNUxRA7v7eZxSMwHM8uSdDZ3mMX",
+                    "t2AFEP4PCPyFpD2EKYRwMFC4hmrPGaCJHSG",
+                ],
+                Receiver::ZF => &[
+                    "t27eWDgjFYJGVXmzrXeVjnb5J3uXDM9xH9v",
+                    "t2FuSwoLCdBVPwdZuYoHrEzxAb9qy4qjbnL",
+                    "t2GeJQXb2vtbVRNcYyxqjc5aHwuCjxUj5op",
+                ],
+                Receiver::MG => &[
+                    "t2Gvxv2ZdBDHQvUUp4dGRAn3mRxtUC3ymwf",
+                    "t2D3gZx2gp3u6Vd8BBbyBhWQxjCK7jmTGrt",
+                    "t2EitNtm9xZn2QCsYorLzNEHyCtB4V3NcDh",
+                ],
+            }
+        }
     }
 }
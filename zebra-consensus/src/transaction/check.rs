@@ -2,11 +2,17 @@
 //!
 //! Code in this file can freely assume that no pre-V4 transactions are present.
 
+use group::{Group, GroupEncoding};
+use pasta_curves::pallas;
+use rayon::prelude::*;
+use reddsa::batch;
+
 use zebra_chain::{
-    amount::{Amount, NonNegative},
-    orchard::Flags,
-    sapling::{Output, PerSpendAnchor, Spend},
+    amount::{Amount, NegativeAllowed, NonNegative},
+    orchard::{self, Flags},
+    sapling::{self, Output, PerSpendAnchor, Spend},
     transaction::Transaction,
+    value_balance::ValueBalance,
 };
 
 use crate::error::TransactionError;
@@ -109,6 +115,267 @@ pub fn output_cv_epk_not_small_order(output: &Output) -> Result<(), TransactionE
     }
 }
 
+/// Check that an Orchard Action's cv and rk are not of small order,
+/// i.e. [h_P]cv MUST NOT be 𝒪_P and [h_P]rk MUST NOT be 𝒪_P.
+///
+/// https://zips.z.cash/protocol/protocol.pdf#actiondesc
+pub fn action_cv_rk_not_small_order(action: &orchard::Action) -> Result<(), TransactionError> {
+    if bool::from(action.cv.0.is_small_order()) || bool::from(action.rk.is_small_order()) {
+        Err(TransactionError::SmallOrder)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks every Sapling spend's and output's small-order points, every
+/// Orchard action's small-order points, and every spend-auth and binding
+/// signature in `tx` (both Sapling RedJubjub and Orchard RedPallas), using
+/// data-parallel and batched verification instead of checking one descriptor
+/// at a time.
+///
+/// `sighash` is the transaction's signature hash, as used by every spend-auth
+/// and binding signature in `tx`.
+///
+/// The cv/rk/epk small-order checks are independent per descriptor, so they
+/// run across all spends, outputs, and actions concurrently via `rayon`. The
+/// RedJubjub and RedPallas signatures are each accumulated into their own
+/// batch verifier (including the pool's binding signature) and checked in
+/// one aggregated operation per pool; if a batch fails, its signatures are
+/// re-checked individually so the error can name the specific descriptor (or
+/// the binding signature) that's invalid.
+pub fn shielded_balances_and_points_valid(
+    tx: &Transaction,
+    sighash: [u8; 32],
+) -> Result<(), TransactionError> {
+    let spends: Vec<_> = tx.sapling_spends_per_anchor().collect();
+    let outputs: Vec<_> = tx.sapling_outputs().collect();
+    let actions: Vec<_> = tx.orchard_actions().collect();
+
+    spends
+        .par_iter()
+        .try_for_each(|spend| spend_cv_rk_not_small_order(spend))?;
+    outputs
+        .par_iter()
+        .try_for_each(|output| output_cv_epk_not_small_order(output))?;
+    actions
+        .par_iter()
+        .try_for_each(|action| action_cv_rk_not_small_order(action))?;
+
+    if let Some(sapling) = tx.sapling_shielded_data() {
+        batch_verify_sapling_signatures(
+            &spends,
+            &outputs,
+            tx.sapling_value_balance(),
+            sapling.binding_sig,
+            sighash,
+        )?;
+    }
+    if let Some(orchard_shielded_data) = tx.orchard_shielded_data() {
+        batch_verify_orchard_signatures(
+            &actions,
+            tx.orchard_value_balance(),
+            orchard_shielded_data.binding_sig,
+            sighash,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Batch-verifies every spend's RedJubjub spend-auth signature, plus the
+/// bundle's RedJubjub binding signature, over `sighash`, falling back to
+/// individual verification if the batch fails.
+fn batch_verify_sapling_signatures(
+    spends: &[&Spend<PerSpendAnchor>],
+    outputs: &[&Output],
+    value_balance: Amount<NegativeAllowed>,
+    binding_sig: reddsa::Signature<reddsa::sapling::Binding>,
+    sighash: [u8; 32],
+) -> Result<(), TransactionError> {
+    let bvk = sapling_binding_verification_key(spends, outputs, value_balance);
+
+    let mut verifier = batch::Verifier::new();
+    for spend in spends {
+        verifier.queue((spend.rk.into(), spend.spend_auth_sig.into(), &sighash[..]));
+    }
+    verifier.queue((bvk, binding_sig, &sighash[..]));
+
+    if verifier.verify(rand::thread_rng()).is_ok() {
+        return Ok(());
+    }
+
+    // The batch failed: check each signature individually so we can report
+    // which specific spend (or the binding signature) is invalid.
+    for (index, spend) in spends.iter().enumerate() {
+        let mut individual = batch::Verifier::new();
+        individual.queue((spend.rk.into(), spend.spend_auth_sig.into(), &sighash[..]));
+        individual
+            .verify(rand::thread_rng())
+            .map_err(|_| TransactionError::InvalidSpendAuthSignature { index })?;
+    }
+
+    let mut individual = batch::Verifier::new();
+    individual.queue((bvk, binding_sig, &sighash[..]));
+    individual
+        .verify(rand::thread_rng())
+        .map_err(|_| TransactionError::InvalidSaplingBindingSignature)?;
+
+    Ok(())
+}
+
+/// Batch-verifies every action's RedPallas spend-auth signature, plus the
+/// bundle's RedPallas binding signature, over `sighash`, falling back to
+/// individual verification if the batch fails.
+fn batch_verify_orchard_signatures(
+    actions: &[&orchard::Action],
+    value_balance: Amount<NegativeAllowed>,
+    binding_sig: reddsa::Signature<reddsa::orchard::Binding>,
+    sighash: [u8; 32],
+) -> Result<(), TransactionError> {
+    let bvk = orchard_binding_verification_key(actions, value_balance);
+
+    let mut verifier = batch::Verifier::new();
+    for action in actions {
+        verifier.queue((action.rk.into(), action.spend_auth_sig.into(), &sighash[..]));
+    }
+    verifier.queue((bvk, binding_sig, &sighash[..]));
+
+    if verifier.verify(rand::thread_rng()).is_ok() {
+        return Ok(());
+    }
+
+    // The batch failed: check each signature individually so we can report
+    // which specific action (or the binding signature) is invalid.
+    for (index, action) in actions.iter().enumerate() {
+        let mut individual = batch::Verifier::new();
+        individual.queue((action.rk.into(), action.spend_auth_sig.into(), &sighash[..]));
+        individual
+            .verify(rand::thread_rng())
+            .map_err(|_| TransactionError::InvalidOrchardSpendAuthSignature { index })?;
+    }
+
+    let mut individual = batch::Verifier::new();
+    individual.queue((bvk, binding_sig, &sighash[..]));
+    individual
+        .verify(rand::thread_rng())
+        .map_err(|_| TransactionError::InvalidOrchardBindingSignature)?;
+
+    Ok(())
+}
+
+/// Derives the Sapling binding signature's RedJubjub verification key from
+/// `spends`' and `outputs`' value commitments and the bundle's `value_balance`:
+///
+/// `cv_net = sum(cv_spends) - sum(cv_outputs) - [value_balance]ValueCommitment(rcv=0)`
+///
+/// Every value commitment is `[v]VCV + [rcv]RCV` for fixed generators `VCV`/`RCV`,
+/// so `cv_net` collapses to `[rcv_balance]RCV` where `rcv_balance = sum(rcv_spends)
+/// - sum(rcv_outputs)`: the `[value_balance]VCV` term it would otherwise carry is
+/// exactly cancelled by subtracting `ValueCommitment::from(value_balance)`, which is
+/// the trivial (`rcv = 0`) commitment to that same value. This leaves `cv_net` as a
+/// valid RedJubjub verification key for the private key `rcv_balance`, which is
+/// exactly what the binding signature proves knowledge of.
+fn sapling_binding_verification_key(
+    spends: &[&Spend<PerSpendAnchor>],
+    outputs: &[&Output],
+    value_balance: Amount<NegativeAllowed>,
+) -> reddsa::VerificationKeyBytes<reddsa::sapling::Binding> {
+    sapling_cv_net(
+        spends.iter().map(|spend| spend.cv.0),
+        outputs.iter().map(|output| output.cv.0),
+        value_balance,
+    )
+    .to_bytes()
+    .into()
+}
+
+/// The Sapling `cv_net` computation itself, taking raw value commitment
+/// points rather than full `Spend`/`Output` descriptors so it can be
+/// unit-tested without constructing either.
+fn sapling_cv_net(
+    cv_spends: impl Iterator<Item = jubjub::ExtendedPoint>,
+    cv_outputs: impl Iterator<Item = jubjub::ExtendedPoint>,
+    value_balance: Amount<NegativeAllowed>,
+) -> jubjub::ExtendedPoint {
+    let cv_spends = cv_spends.fold(jubjub::ExtendedPoint::identity(), |acc, cv| acc + cv);
+    let cv_outputs = cv_outputs.fold(jubjub::ExtendedPoint::identity(), |acc, cv| acc + cv);
+    let cv_balance = sapling::ValueCommitment::from(value_balance).0;
+
+    cv_spends - cv_outputs - cv_balance
+}
+
+/// Derives the Orchard binding signature's RedPallas verification key from
+/// `actions`' value commitments and the bundle's `value_balance`, the same way
+/// [`sapling_binding_verification_key`] does for Sapling.
+fn orchard_binding_verification_key(
+    actions: &[&orchard::Action],
+    value_balance: Amount<NegativeAllowed>,
+) -> reddsa::VerificationKeyBytes<reddsa::orchard::Binding> {
+    orchard_cv_net(actions.iter().map(|action| action.cv.0), value_balance)
+        .to_bytes()
+        .into()
+}
+
+/// The Orchard `cv_net` computation itself, taking raw value commitment
+/// points rather than full `Action` descriptors so it can be unit-tested
+/// without constructing one.
+fn orchard_cv_net(
+    cv_actions: impl Iterator<Item = pallas::Point>,
+    value_balance: Amount<NegativeAllowed>,
+) -> pallas::Point {
+    let cv_actions = cv_actions.fold(pallas::Point::identity(), |acc, cv| acc + cv);
+    let cv_balance = orchard::ValueCommitment::from(value_balance).0;
+
+    cv_actions - cv_balance
+}
+
+/// Checks that the transaction's value balance is non-negative, and that
+/// every intermediate pool amount stays within the valid Zcash money range.
+///
+/// Sums `transparent_input_values` (the resolved value of every transparent
+/// input the transaction spends) and subtracts the transparent outputs, adds
+/// the Sapling and Orchard value balances, and adds the JoinSplit
+/// `vpub_new`/`vpub_old` contributions, then checks that the combined
+/// remaining value is non-negative.
+///
+/// [Consensus rule]: https://zips.z.cash/protocol/protocol.pdf#transactions
+pub fn value_balance_and_remaining_value(
+    tx: &Transaction,
+    transparent_input_values: &[Amount<NonNegative>],
+) -> Result<(), TransactionError> {
+    let transparent_in = sum_amounts(transparent_input_values.iter().copied())?;
+    let transparent_out = sum_amounts(tx.outputs().iter().map(|output| output.value))?;
+
+    let transparent_balance = (transparent_in - transparent_out)
+        .map_err(|_| TransactionError::InvalidAmount)?;
+
+    let value_balance = ValueBalance::<NegativeAllowed>::new(
+        Some(transparent_balance),
+        Some(tx.joinsplit_value_balance()),
+        Some(tx.sapling_value_balance()),
+        Some(tx.orchard_value_balance()),
+    );
+
+    value_balance
+        .remaining_transaction_value()
+        .map_err(|_| TransactionError::InvalidAmount)?;
+
+    Ok(())
+}
+
+/// Sums `amounts`, rejecting any amount or partial sum that falls outside the
+/// valid Zcash money range.
+fn sum_amounts(
+    amounts: impl Iterator<Item = Amount<NonNegative>>,
+) -> Result<Amount<NonNegative>, TransactionError> {
+    amounts
+        .try_fold(
+            Amount::<NonNegative>::try_from(0).expect("an amount of 0 is always valid"),
+            |acc, amount| acc + amount,
+        )
+        .map_err(|_| TransactionError::InvalidAmount)
+}
+
 /// Check if a transaction is using the diabled sprout pool.
 ///
 /// This check should be made only if the transaction block is above certain
@@ -129,3 +396,60 @@ pub fn disabled_sprout_pool(tx: &Transaction) -> Result<(), TransactionError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A legitimately-signed Sapling binding signature, for a bundle with a
+    /// single spend whose value commitment carries the signing key's
+    /// trapdoor, no outputs, and a zero value balance, must verify against
+    /// the `cv_net` [`sapling_cv_net`] derives for it.
+    #[test]
+    fn sapling_cv_net_accepts_a_real_binding_signature() {
+        let mut rng = rand::thread_rng();
+        let sighash = [7u8; 32];
+        let zero = Amount::<NegativeAllowed>::try_from(0).expect("0 is a valid amount");
+
+        let sk = reddsa::SigningKey::<reddsa::sapling::Binding>::new(&mut rng);
+        let vk_bytes: reddsa::VerificationKeyBytes<reddsa::sapling::Binding> =
+            reddsa::VerificationKey::from(&sk).into();
+        let signature = sk.sign(&mut rng, &sighash);
+
+        let cv_spend: jubjub::ExtendedPoint = jubjub::AffinePoint::from_bytes(vk_bytes.into())
+            .unwrap()
+            .into();
+
+        let bvk = sapling_cv_net(std::iter::once(cv_spend), std::iter::empty(), zero)
+            .to_bytes()
+            .into();
+
+        let mut verifier = batch::Verifier::new();
+        verifier.queue((bvk, signature, &sighash[..]));
+        assert!(verifier.verify(rng).is_ok());
+    }
+
+    /// The Orchard equivalent of
+    /// [`tests::sapling_cv_net_accepts_a_real_binding_signature`].
+    #[test]
+    fn orchard_cv_net_accepts_a_real_binding_signature() {
+        let mut rng = rand::thread_rng();
+        let sighash = [7u8; 32];
+        let zero = Amount::<NegativeAllowed>::try_from(0).expect("0 is a valid amount");
+
+        let sk = reddsa::SigningKey::<reddsa::orchard::Binding>::new(&mut rng);
+        let vk_bytes: reddsa::VerificationKeyBytes<reddsa::orchard::Binding> =
+            reddsa::VerificationKey::from(&sk).into();
+        let signature = sk.sign(&mut rng, &sighash);
+
+        let cv_action = pallas::Point::from_bytes(&vk_bytes.into()).unwrap();
+
+        let bvk = orchard_cv_net(std::iter::once(cv_action), zero)
+            .to_bytes()
+            .into();
+
+        let mut verifier = batch::Verifier::new();
+        verifier.queue((bvk, signature, &sighash[..]));
+        assert!(verifier.verify(rng).is_ok());
+    }
+}
@@ -6,20 +6,32 @@
 //! Some parts of the `zcashd` RPC documentation are outdated.
 //! So this implementation follows the `lightwalletd` client implementation.
 
+use std::io::Cursor;
+
 use futures::FutureExt;
 use jsonrpc_core::{self, BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 use tower::{buffer::Buffer, util::BoxService, ServiceExt};
 
-use zebra_chain::block::Height;
+use zebra_chain::{
+    block::Height,
+    parameters::{Network, NetworkUpgrade},
+    serialization::ZcashDeserialize,
+    transaction::Transaction,
+};
 use zebra_network::constants::USER_AGENT;
+use zebra_node_services::mempool;
+use zebra_state::{HashOrHeight, NoteCommitmentSubtreeIndex};
 
 type State = Buffer<
     BoxService<zebra_state::Request, zebra_state::Response, zebra_state::BoxError>,
     zebra_state::Request,
 >;
 
+type Mempool =
+    Buffer<BoxService<mempool::Request, mempool::Response, zebra_node_services::BoxError>, mempool::Request>;
+
 #[cfg(test)]
 mod tests;
 
@@ -51,25 +63,135 @@ pub trait Rpc {
 
     /// getblockchaininfo
     ///
-    /// TODO: explain what the method does
-    ///       link to the zcashd RPC reference
-    ///       list the arguments and fields that lightwalletd uses
-    ///       note any other lightwalletd changes
+    /// Returns blockchain state information for the best chain known to Zebra.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/getblockchaininfo.html>
+    ///
+    /// lightwalletd uses `blocks` (to track sync progress) and `consensus`
+    /// (to pick the branch id it must use when building transactions).
     #[rpc(name = "getblockchaininfo")]
-    fn get_blockchain_info(&self) -> Result<GetBlockChainInfo>;
+    fn get_blockchain_info(&self) -> BoxFuture<Result<GetBlockChainInfo>>;
+
+    /// getblockcount
+    ///
+    /// Returns the height of the best chain known to Zebra.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/getblockcount.html>
+    #[rpc(name = "getblockcount")]
+    fn get_block_count(&self) -> BoxFuture<Result<u32>>;
+
+    /// getbestblockhash
+    ///
+    /// Returns the hash of the tip block of the best chain known to Zebra.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/getbestblockhash.html>
+    #[rpc(name = "getbestblockhash")]
+    fn get_best_block_hash(&self) -> BoxFuture<Result<String>>;
 
     /// getblock
     ///
-    /// Returns ...
+    /// Returns the requested block, either as raw hex data or as a JSON
+    /// object, depending on `verbosity`.
     ///
     /// zcashd reference: <https://zcash.github.io/rpc/getblock.html>
     ///
+    /// # Parameters
+    ///
+    /// - `height`: the height of the block to return.
+    /// - `verbosity`: `0` returns the raw block as a hex string; any other
+    ///   value (the default) returns a JSON object with the block's hash,
+    ///   confirmations, height, and the txids it contains.
+    #[rpc(name = "getblock")]
+    fn get_block(&self, height: Height, verbosity: Option<u8>) -> BoxFuture<Result<GetBlock>>;
+
+    /// getrawmempool
+    ///
+    /// Returns the txids of the transactions currently in Zebra's mempool.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/getrawmempool.html>
+    #[rpc(name = "getrawmempool")]
+    fn get_raw_mempool(&self) -> BoxFuture<Result<Vec<String>>>;
+
+    /// getrawtransaction
+    ///
+    /// Returns the requested transaction, either as raw hex data or as a
+    /// JSON object, depending on `verbose`. Looks the transaction up in the
+    /// finalized and non-finalized state only: a transaction that is only in
+    /// the mempool is not found.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/getrawtransaction.html>
+    ///
+    /// # Parameters
+    ///
+    /// - `txid`: the transaction id, as a hex string.
+    /// - `verbose`: `0` (the default) returns the raw transaction as a hex
+    ///   string; any other value returns a JSON object.
+    #[rpc(name = "getrawtransaction")]
+    fn get_raw_transaction(
+        &self,
+        txid: String,
+        verbose: Option<u8>,
+    ) -> BoxFuture<Result<GetRawTransaction>>;
+
+    /// sendrawtransaction
+    ///
+    /// Submits a hex-encoded raw transaction to Zebra's mempool.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/sendrawtransaction.html>
+    #[rpc(name = "sendrawtransaction")]
+    fn send_raw_transaction(&self, raw_transaction_hex: String) -> BoxFuture<Result<SentTransactionHash>>;
+
+    /// z_gettreestate
+    ///
+    /// Returns the roots of the Sapling and Orchard note commitment trees at
+    /// the given height or hash. The trees' frontiers are not returned.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/z_gettreestate.html>
+    #[rpc(name = "z_gettreestate")]
+    fn z_get_treestate(&self, hash_or_height: String) -> BoxFuture<Result<GetTreestate>>;
+
+    /// getaddresstxids
+    ///
+    /// Returns the txids of transactions that spent or received from any of
+    /// `request.addresses`, within `request.start`..=`request.end`.
+    ///
+    /// zcashd reference: <https://zcash.github.io/rpc/getaddresstxids.html>
+    #[rpc(name = "getaddresstxids")]
+    fn get_address_tx_ids(&self, request: GetAddressTxIdsRequest) -> BoxFuture<Result<Vec<String>>>;
+
+    /// z_getsubtreesbyindex
+    ///
+    /// Returns the Sapling or Orchard note commitment subtree roots, in index order,
+    /// starting at `start_index`. Used by `lightwalletd` to bootstrap a compact
+    /// skeleton of the commitment tree instead of scanning every note.
+    ///
     /// Result:
     /// {
-    ///      "data": String, // Add comment
+    ///      "pool": String, // "sapling" or "orchard"
+    ///      "start_index": u16,
+    ///      "subtrees": [{ "index": u16, "root": String, "end_height": u32 }, ...],
     /// }
-    #[rpc(name = "getblock")]
-    fn get_block(&self, height: Height) -> BoxFuture<Result<GetBlock>>;
+    #[rpc(name = "z_getsubtreesbyindex")]
+    fn z_get_subtrees_by_index(
+        &self,
+        pool: String,
+        start_index: u16,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<GetSubtreesByIndex>>;
+}
+
+/// Returns the number of confirmations a block at `height` has, given that
+/// `tip_height` is the current best chain tip.
+///
+/// A block looked up by height (rather than by a possibly-orphaned hash) is
+/// always on the best chain, so this is only ever `-1` if the tip somehow
+/// ends up behind `height`.
+fn block_confirmations(tip_height: Height, height: Height) -> i64 {
+    if tip_height >= height {
+        (tip_height.0 - height.0) as i64 + 1
+    } else {
+        -1
+    }
 }
 
 /// RPC method implementations.
@@ -77,8 +199,15 @@ pub trait Rpc {
 pub struct RpcImpl {
     /// Zebra's application version.
     pub app_version: String,
+
+    /// The network Zebra is configured to validate.
+    pub network: Network,
+
     pub state_service: State,
+
+    pub mempool_service: Mempool,
 }
+
 impl Rpc for RpcImpl {
     fn get_info(&self) -> Result<GetInfo> {
         let response = GetInfo {
@@ -89,31 +218,326 @@ impl Rpc for RpcImpl {
         Ok(response)
     }
 
-    fn get_blockchain_info(&self) -> Result<GetBlockChainInfo> {
-        // TODO: dummy output data, fix in the context of #3143
-        let response = GetBlockChainInfo {
-            chain: "TODO: main".to_string(),
-        };
+    fn get_blockchain_info(&self) -> BoxFuture<Result<GetBlockChainInfo>> {
+        let state = self.state_service.clone();
+        let network = self.network;
 
-        Ok(response)
+        async move {
+            let res = state
+                .oneshot(zebra_state::Request::Tip)
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            let (tip_height, tip_hash) = match res {
+                zebra_state::Response::Tip(Some((height, hash))) => (height, hash),
+                zebra_state::Response::Tip(None) => (Height(0), zebra_chain::block::Hash([0; 32])),
+                _ => unreachable!("wrong response to a Tip request"),
+            };
+
+            let current_upgrade = NetworkUpgrade::current(network, tip_height);
+            let next_upgrade = NetworkUpgrade::current(network, (tip_height + 1).unwrap_or(tip_height));
+
+            Ok(GetBlockChainInfo {
+                chain: network.to_string(),
+                blocks: tip_height,
+                best_block_hash: tip_hash,
+                estimated_height: tip_height,
+                consensus: TipConsensusBranch {
+                    chain_tip: format!("{:08x}", current_upgrade.branch_id().unwrap_or(0)),
+                    next_block: format!("{:08x}", next_upgrade.branch_id().unwrap_or(0)),
+                },
+            })
+        }
+        .boxed()
+    }
+
+    fn get_block_count(&self) -> BoxFuture<Result<u32>> {
+        let state = self.state_service.clone();
+
+        async move {
+            let res = state
+                .oneshot(zebra_state::Request::Tip)
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            match res {
+                zebra_state::Response::Tip(Some((height, _hash))) => Ok(height.0),
+                zebra_state::Response::Tip(None) => {
+                    Err(jsonrpc_core::Error::invalid_request().into())
+                }
+                _ => unreachable!("wrong response to a Tip request"),
+            }
+        }
+        .boxed()
+    }
+
+    fn get_best_block_hash(&self) -> BoxFuture<Result<String>> {
+        let state = self.state_service.clone();
+
+        async move {
+            let res = state
+                .oneshot(zebra_state::Request::Tip)
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            match res {
+                zebra_state::Response::Tip(Some((_height, hash))) => Ok(hash.to_string()),
+                zebra_state::Response::Tip(None) => {
+                    Err(jsonrpc_core::Error::invalid_request().into())
+                }
+                _ => unreachable!("wrong response to a Tip request"),
+            }
+        }
+        .boxed()
     }
 
-    fn get_block(&self, height: Height) -> BoxFuture<Result<GetBlock>> {
+    fn get_block(&self, height: Height, verbosity: Option<u8>) -> BoxFuture<Result<GetBlock>> {
         let state = self.state_service.clone();
+        let verbosity = verbosity.unwrap_or(1);
 
         async move {
             let res = state
-                .oneshot(zebra_state::Request::Block(
-                    zebra_state::HashOrHeight::Height(height),
-                ))
+                .clone()
+                .oneshot(zebra_state::Request::Block(HashOrHeight::Height(height)))
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            match res {
+                zebra_state::Response::Block(Some(block)) => {
+                    if verbosity == 0 {
+                        Ok(GetBlock::Raw(hex::encode(block.zcash_serialize_to_vec()?)))
+                    } else {
+                        let tip_res = state
+                            .oneshot(zebra_state::Request::Tip)
+                            .await
+                            .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+                        let tip_height = match tip_res {
+                            zebra_state::Response::Tip(Some((tip_height, _hash))) => tip_height,
+                            zebra_state::Response::Tip(None) => height,
+                            _ => unreachable!("wrong response to a Tip request"),
+                        };
+
+                        let confirmations = block_confirmations(tip_height, height);
+
+                        Ok(GetBlock::Object {
+                            hash: block.hash().to_string(),
+                            confirmations,
+                            height: Some(height.0),
+                            tx: block
+                                .transactions
+                                .iter()
+                                .map(|tx| tx.hash().to_string())
+                                .collect(),
+                        })
+                    }
+                }
+                zebra_state::Response::Block(None) => Err(jsonrpc_core::Error::invalid_params(
+                    "block height not found",
+                )),
+                _ => unreachable!("wrong response to a Block request"),
+            }
+        }
+        .boxed()
+    }
+
+    fn get_raw_mempool(&self) -> BoxFuture<Result<Vec<String>>> {
+        let mempool = self.mempool_service.clone();
+
+        async move {
+            let res = mempool
+                .oneshot(mempool::Request::TransactionIds)
                 .await
-                .map_err(|error| error.to_string());
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
 
-            match res.unwrap() {
-                zebra_state::Response::Block(Some(block)) => Ok(GetBlock {
-                    data: block.to_string(),
+            match res {
+                mempool::Response::TransactionIds(ids) => {
+                    Ok(ids.into_iter().map(|id| id.mined_id().to_string()).collect())
+                }
+                _ => unreachable!("wrong response to a TransactionIds request"),
+            }
+        }
+        .boxed()
+    }
+
+    fn get_raw_transaction(
+        &self,
+        txid: String,
+        verbose: Option<u8>,
+    ) -> BoxFuture<Result<GetRawTransaction>> {
+        let state = self.state_service.clone();
+        let verbose = verbose.unwrap_or(0) != 0;
+
+        async move {
+            let hash: zebra_chain::transaction::Hash = txid
+                .parse()
+                .map_err(|_| jsonrpc_core::Error::invalid_params("invalid txid"))?;
+
+            let res = state
+                .oneshot(zebra_state::Request::Transaction(hash))
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            let transaction = match res {
+                zebra_state::Response::Transaction(Some(transaction)) => transaction,
+                zebra_state::Response::Transaction(None) => {
+                    return Err(jsonrpc_core::Error::invalid_params(
+                        "transaction not found",
+                    ))
+                }
+                _ => unreachable!("wrong response to a Transaction request"),
+            };
+
+            let hex = hex::encode(transaction.zcash_serialize_to_vec()?);
+
+            if verbose {
+                Ok(GetRawTransaction::Object(TransactionObject {
+                    hex,
+                    txid: hash.to_string(),
+                }))
+            } else {
+                Ok(GetRawTransaction::Raw(hex))
+            }
+        }
+        .boxed()
+    }
+
+    fn send_raw_transaction(
+        &self,
+        raw_transaction_hex: String,
+    ) -> BoxFuture<Result<SentTransactionHash>> {
+        let mempool = self.mempool_service.clone();
+
+        async move {
+            let raw_transaction_bytes = hex::decode(raw_transaction_hex)
+                .map_err(|_| jsonrpc_core::Error::invalid_params("raw transaction is not hex"))?;
+            let transaction = Transaction::zcash_deserialize(Cursor::new(raw_transaction_bytes))
+                .map_err(|_| {
+                    jsonrpc_core::Error::invalid_params("raw transaction could not be decoded")
+                })?;
+            let unmined_transaction = mempool::Gossip::Tx(transaction.into());
+
+            let res = mempool
+                .oneshot(mempool::Request::Queue(vec![unmined_transaction.clone()]))
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            match res {
+                mempool::Response::Queued(mut results) => {
+                    let result = results
+                        .pop()
+                        .expect("queueing a single transaction returns a single result");
+                    match result {
+                        Ok(()) => Ok(SentTransactionHash(unmined_transaction.id().mined_id())),
+                        Err(error) => Err(jsonrpc_core::Error::invalid_params(error.to_string())),
+                    }
+                }
+                _ => unreachable!("wrong response to a Queue request"),
+            }
+        }
+        .boxed()
+    }
+
+    fn z_get_treestate(&self, hash_or_height: String) -> BoxFuture<Result<GetTreestate>> {
+        let state = self.state_service.clone();
+
+        async move {
+            let hash_or_height: HashOrHeight = hash_or_height
+                .parse()
+                .map_err(|_| jsonrpc_core::Error::invalid_params("invalid hash or height"))?;
+
+            let sapling = state
+                .clone()
+                .oneshot(zebra_state::Request::SaplingTree(hash_or_height))
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+            let orchard = state
+                .oneshot(zebra_state::Request::OrchardTree(hash_or_height))
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            let sapling_root = match sapling {
+                zebra_state::Response::SaplingTree(tree) => tree.map(|tree| tree.root().to_string()),
+                _ => unreachable!("wrong response to a SaplingTree request"),
+            };
+            let orchard_root = match orchard {
+                zebra_state::Response::OrchardTree(tree) => tree.map(|tree| tree.root().to_string()),
+                _ => unreachable!("wrong response to an OrchardTree request"),
+            };
+
+            Ok(GetTreestate {
+                hash: hash_or_height.hash().map(|hash| hash.to_string()),
+                height: hash_or_height.height().map(|height| height.0),
+                sapling: sapling_root.map(|root| TreestateTree { commitments: root }),
+                orchard: orchard_root.map(|root| TreestateTree { commitments: root }),
+            })
+        }
+        .boxed()
+    }
+
+    fn get_address_tx_ids(&self, request: GetAddressTxIdsRequest) -> BoxFuture<Result<Vec<String>>> {
+        let state = self.state_service.clone();
+
+        async move {
+            let res = state
+                .oneshot(zebra_state::Request::TransactionIdsByAddresses {
+                    addresses: request.addresses,
+                    height_range: Height(request.start.unwrap_or(0))
+                        ..=Height(request.end.unwrap_or(u32::MAX)),
+                })
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            match res {
+                zebra_state::Response::AddressesTransactionIds(ids) => {
+                    Ok(ids.into_iter().map(|(_location, id)| id.to_string()).collect())
+                }
+                _ => unreachable!("wrong response to a TransactionIdsByAddresses request"),
+            }
+        }
+        .boxed()
+    }
+
+    fn z_get_subtrees_by_index(
+        &self,
+        pool: String,
+        start_index: u16,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<GetSubtreesByIndex>> {
+        let state = self.state_service.clone();
+        // When no limit is given, return up to `u16::MAX` (65535) subtrees.
+        let limit = limit.unwrap_or(u16::MAX);
+        let start_index = NoteCommitmentSubtreeIndex(start_index);
+
+        async move {
+            let request = match pool.as_str() {
+                "sapling" => zebra_state::Request::SaplingSubtrees { start_index, limit },
+                "orchard" => zebra_state::Request::OrchardSubtrees { start_index, limit },
+                _ => return Err(jsonrpc_core::Error::invalid_params(
+                    "pool must be \"sapling\" or \"orchard\"",
+                )),
+            };
+
+            let res = state
+                .oneshot(request)
+                .await
+                .map_err(|error| jsonrpc_core::Error::invalid_params(error.to_string()))?;
+
+            match res {
+                zebra_state::Response::SaplingSubtrees(subtrees)
+                | zebra_state::Response::OrchardSubtrees(subtrees) => Ok(GetSubtreesByIndex {
+                    pool,
+                    start_index: start_index.0,
+                    subtrees: subtrees
+                        .into_iter()
+                        .map(|(index, data)| SubtreeRpcData {
+                            index: index.0,
+                            root: data.root.to_string(),
+                            end_height: data.end_height.0,
+                        })
+                        .collect(),
                 }),
-                _ => unreachable!("whatever"),
+                _ => unreachable!("wrong response to a SaplingSubtrees/OrchardSubtrees request"),
             }
         }
         .boxed()
@@ -131,11 +555,140 @@ pub struct GetInfo {
 /// Response to a `getblockchaininfo` RPC request.
 pub struct GetBlockChainInfo {
     chain: String,
-    // TODO: add other fields used by lightwalletd (#3143)
+    blocks: Height,
+    best_block_hash: zebra_chain::block::Hash,
+    estimated_height: Height,
+    consensus: TipConsensusBranch,
+    // TODO: add `value_pools` and `upgrades` once Zebra tracks the finalized
+    // value pool balances and the full network upgrade activation table
+    // (tracked in the same work as #3143).
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// The consensus branch ids used by the tip block, and the block after it.
+///
+/// lightwalletd uses `next_block` to pick the branch id for transactions it
+/// is about to broadcast.
+pub struct TipConsensusBranch {
+    /// The consensus branch id used to validate the current chain tip, as a hex string.
+    chain_tip: String,
+    /// The consensus branch id the next block must use, as a hex string.
+    next_block: String,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
 /// Response to a `getblock` RPC request.
-pub struct GetBlock {
-    data: String,
+pub enum GetBlock {
+    /// The block, as raw hex-encoded bytes. Used when the request's
+    /// `verbosity` argument is 0.
+    Raw(String),
+    /// The block's fields, used when `verbosity` is non-zero.
+    Object {
+        /// The block hash.
+        hash: String,
+        /// The number of confirmations, or -1 if the block isn't in the best chain.
+        confirmations: i64,
+        /// The block's height.
+        height: Option<u32>,
+        /// The txids of the transactions in the block, in block order.
+        tx: Vec<String>,
+    },
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+/// Response to a `getrawtransaction` RPC request.
+pub enum GetRawTransaction {
+    /// The transaction, as raw hex-encoded bytes. Used when the request's
+    /// `verbose` argument is 0.
+    Raw(String),
+    /// The transaction's fields, used when `verbose` is non-zero.
+    Object(TransactionObject),
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// The verbose fields of a `getrawtransaction` response.
+pub struct TransactionObject {
+    /// The raw transaction, as hex-encoded bytes.
+    hex: String,
+    /// The transaction id.
+    txid: String,
+}
+
+/// Response to a `sendrawtransaction` RPC request.
+pub struct SentTransactionHash(zebra_chain::transaction::Hash);
+
+impl serde::Serialize for SentTransactionHash {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SentTransactionHash {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let hex = String::deserialize(deserializer)?;
+        hex.parse()
+            .map(SentTransactionHash)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// Response to a `z_gettreestate` RPC request.
+pub struct GetTreestate {
+    /// The hash of the block the treestate is for.
+    hash: Option<String>,
+    /// The height of the block the treestate is for.
+    height: Option<u32>,
+    /// The Sapling note commitment tree root, if the pool exists at this height.
+    sapling: Option<TreestateTree>,
+    /// The Orchard note commitment tree root, if the pool exists at this height.
+    orchard: Option<TreestateTree>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// A single shielded pool's note commitment tree root.
+pub struct TreestateTree {
+    /// The root of the note commitment tree, as a hex string.
+    commitments: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// Parameters for a `getaddresstxids` RPC request.
+pub struct GetAddressTxIdsRequest {
+    /// The transparent addresses to query.
+    addresses: Vec<String>,
+    /// The start of the height range to search, inclusive. Defaults to the genesis block.
+    start: Option<u32>,
+    /// The end of the height range to search, inclusive. Defaults to the chain tip.
+    end: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// A single entry in a `z_getsubtreesbyindex` response.
+pub struct SubtreeRpcData {
+    /// The index of this subtree.
+    index: u16,
+    /// The root of this subtree, as a hex string.
+    root: String,
+    /// The height of the block that completed this subtree.
+    end_height: u32,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// Response to a `z_getsubtreesbyindex` RPC request.
+pub struct GetSubtreesByIndex {
+    /// The pool these subtrees are from, either "sapling" or "orchard".
+    pool: String,
+    /// The index of the first subtree in `subtrees`.
+    start_index: u16,
+    /// The list of subtree roots, in index order.
+    subtrees: Vec<SubtreeRpcData>,
 }
@@ -0,0 +1,139 @@
+//! Tests for Zebra's RPC method implementations.
+
+use tower::{buffer::Buffer, util::BoxService};
+use tower_test::mock;
+
+use zebra_chain::{block, parameters::Network};
+use zebra_node_services::mempool;
+
+use super::*;
+
+/// Builds an [`RpcImpl`] wired to mock state and mempool services, along with
+/// handles to drive each mock's expected request/response pairs.
+fn mock_rpc() -> (
+    RpcImpl,
+    mock::Handle<zebra_state::Request, zebra_state::Response>,
+    mock::Handle<mempool::Request, mempool::Response>,
+) {
+    let (state_service, state_handle) = mock::pair();
+    let (mempool_service, mempool_handle) = mock::pair();
+
+    let rpc = RpcImpl {
+        app_version: "test".to_string(),
+        network: Network::Mainnet,
+        state_service: Buffer::new(BoxService::new(state_service), 1),
+        mempool_service: Buffer::new(BoxService::new(mempool_service), 1),
+    };
+
+    (rpc, state_handle, mempool_handle)
+}
+
+#[test]
+fn block_confirmations_counts_up_from_the_tip() {
+    assert_eq!(block_confirmations(block::Height(10), block::Height(8)), 3);
+    assert_eq!(block_confirmations(block::Height(10), block::Height(10)), 1);
+}
+
+#[test]
+fn block_confirmations_is_negative_one_if_the_tip_is_behind() {
+    assert_eq!(block_confirmations(block::Height(5), block::Height(8)), -1);
+}
+
+#[tokio::test]
+async fn get_block_count_returns_the_tip_height() {
+    let (rpc, mut state_handle, _mempool_handle) = mock_rpc();
+
+    let call = tokio::spawn(rpc.get_block_count());
+
+    let (request, responder) = state_handle.next_request().await.expect("a request");
+    assert!(matches!(request, zebra_state::Request::Tip));
+    responder.send_response(zebra_state::Response::Tip(Some((
+        block::Height(10),
+        block::Hash([0; 32]),
+    ))));
+
+    assert_eq!(call.await.expect("task should not panic").unwrap(), 10);
+}
+
+#[tokio::test]
+async fn get_best_block_hash_returns_the_tip_hash() {
+    let (rpc, mut state_handle, _mempool_handle) = mock_rpc();
+
+    let call = tokio::spawn(rpc.get_best_block_hash());
+
+    let (request, responder) = state_handle.next_request().await.expect("a request");
+    assert!(matches!(request, zebra_state::Request::Tip));
+    let hash = block::Hash([7; 32]);
+    responder.send_response(zebra_state::Response::Tip(Some((block::Height(10), hash))));
+
+    assert_eq!(
+        call.await.expect("task should not panic").unwrap(),
+        hash.to_string()
+    );
+}
+
+#[tokio::test]
+async fn get_raw_mempool_returns_the_queued_txids() {
+    let (rpc, _state_handle, mut mempool_handle) = mock_rpc();
+
+    let call = tokio::spawn(rpc.get_raw_mempool());
+
+    let (request, responder) = mempool_handle.next_request().await.expect("a request");
+    assert!(matches!(request, mempool::Request::TransactionIds));
+    responder.send_response(mempool::Response::TransactionIds(Default::default()));
+
+    assert_eq!(
+        call.await.expect("task should not panic").unwrap(),
+        Vec::<String>::new()
+    );
+}
+
+#[tokio::test]
+async fn get_raw_transaction_rejects_an_invalid_txid() {
+    let (rpc, _state_handle, _mempool_handle) = mock_rpc();
+
+    let result = rpc.get_raw_transaction("not a txid".to_string(), None).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn send_raw_transaction_rejects_non_hex_input() {
+    let (rpc, _state_handle, _mempool_handle) = mock_rpc();
+
+    let result = rpc.send_raw_transaction("not hex".to_string()).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn z_get_treestate_rejects_an_invalid_hash_or_height() {
+    let (rpc, _state_handle, _mempool_handle) = mock_rpc();
+
+    let result = rpc.z_get_treestate("not a hash or height".to_string()).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn get_address_tx_ids_returns_the_matching_txids() {
+    let (rpc, mut state_handle, _mempool_handle) = mock_rpc();
+
+    let request = GetAddressTxIdsRequest {
+        addresses: vec!["t1some".to_string()],
+        start: None,
+        end: None,
+    };
+    let call = tokio::spawn(rpc.get_address_tx_ids(request));
+
+    let (request, responder) = state_handle.next_request().await.expect("a request");
+    assert!(matches!(
+        request,
+        zebra_state::Request::TransactionIdsByAddresses { .. }
+    ));
+    responder.send_response(zebra_state::Response::AddressesTransactionIds(
+        Default::default(),
+    ));
+
+    assert_eq!(
+        call.await.expect("task should not panic").unwrap(),
+        Vec::<String>::new()
+    );
+}
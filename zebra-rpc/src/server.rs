@@ -33,6 +33,8 @@ use crate::methods::{GetBlockTemplateRpcImpl, GetBlockTemplateRpcServer};
 
 pub mod cookie;
 pub mod http_request_compatibility;
+pub mod ipc;
+pub mod types;
 
 #[cfg(test)]
 mod tests;
@@ -214,6 +216,25 @@ impl RpcServer {
             .merge(get_block_template_rpc_impl.into_rpc())
             .unwrap();
 
+        // Also serve the same methods over a local, auth-free Unix domain socket,
+        // for tooling that would rather not open a TCP port.
+        if let Some(ipc_path) = config.ipc_path.clone() {
+            let ipc_rpc_module = rpc_module.clone();
+            let _ipc_server_task: JoinHandle<Result<(), tower::BoxError>> =
+                ipc::spawn_ipc_server(ipc_path, move |request: crate::server::types::JsonRpcRequest| {
+                    let rpc_module = ipc_rpc_module.clone();
+                    async move {
+                        rpc_module
+                            .call::<_, serde_json::Value>(request.method(), request.params().to_vec())
+                            .await
+                            .map_err(|error| crate::server::types::JsonRpcError {
+                                code: -32000,
+                                message: error.to_string(),
+                            })
+                    }
+                });
+        }
+
         let server_task: JoinHandle<Result<(), tower::BoxError>> = tokio::spawn(async move {
             server_instance.start(rpc_module).stopped().await;
             Ok(())
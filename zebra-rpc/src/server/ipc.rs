@@ -0,0 +1,119 @@
+//! Unix-domain-socket IPC transport for the JSON-RPC server. There is no
+//! Windows named pipe transport.
+//!
+//! This gives local tooling (wallets, CLI clients) a JSON-RPC channel that doesn't
+//! require opening a TCP port or using the HTTP auth cookie, by serving the same
+//! [`JsonRpcRequest`]/[`JsonRpcResponse`] framing as the TCP listener over a local
+//! socket instead.
+
+use std::path::PathBuf;
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixListener,
+    task::JoinHandle,
+};
+use tracing::*;
+
+use crate::server::types::{JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+
+/// Spawns a task that serves JSON-RPC requests over a Unix domain socket at `ipc_path`.
+///
+/// Each connection is framed as newline-delimited JSON: one [`JsonRpcRequest`] per
+/// line in, one [`JsonRpcResponse`] (or [`JsonRpcError`]) per line out. Every decoded
+/// request is dispatched through `dispatch`, which callers build from the same method
+/// table the TCP listener serves, so the IPC and TCP transports can never disagree
+/// about what a method call returns.
+///
+/// The socket file is removed before binding (in case a previous, uncleanly shut down
+/// instance left one behind) and again once the task returns.
+///
+/// # Windows
+///
+/// Only a [`UnixListener`] is served: this function has no Windows named pipe
+/// listener.
+pub fn spawn_ipc_server<Dispatch, DispatchFuture>(
+    ipc_path: PathBuf,
+    dispatch: Dispatch,
+) -> JoinHandle<Result<(), tower::BoxError>>
+where
+    Dispatch: Fn(JsonRpcRequest) -> DispatchFuture + Clone + Send + Sync + 'static,
+    DispatchFuture: std::future::Future<Output = Result<serde_json::Value, JsonRpcError>> + Send,
+{
+    tokio::spawn(async move {
+        // Remove a stale socket file left behind by an unclean shutdown.
+        let _ = std::fs::remove_file(&ipc_path);
+
+        let listener = UnixListener::bind(&ipc_path)?;
+        info!("Opened RPC IPC endpoint at {}", ipc_path.display());
+
+        let result = accept_loop(listener, dispatch).await;
+
+        if let Err(error) = std::fs::remove_file(&ipc_path) {
+            warn!(?error, "could not remove the RPC IPC socket file on shutdown");
+        }
+
+        result
+    })
+}
+
+/// Accepts IPC connections until the listener errors, handling each one on its own task.
+async fn accept_loop<Dispatch, DispatchFuture>(
+    listener: UnixListener,
+    dispatch: Dispatch,
+) -> Result<(), tower::BoxError>
+where
+    Dispatch: Fn(JsonRpcRequest) -> DispatchFuture + Clone + Send + Sync + 'static,
+    DispatchFuture: std::future::Future<Output = Result<serde_json::Value, JsonRpcError>> + Send,
+{
+    loop {
+        let (stream, _addr) = listener.accept().await?;
+        let dispatch = dispatch.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = handle_connection(stream, dispatch).await {
+                warn!(?error, "RPC IPC connection closed with an error");
+            }
+        });
+    }
+}
+
+/// Handles a single IPC client connection, dispatching one [`JsonRpcRequest`] per line
+/// and writing back one newline-terminated response per request.
+async fn handle_connection<Dispatch, DispatchFuture>(
+    stream: tokio::net::UnixStream,
+    dispatch: Dispatch,
+) -> Result<(), tower::BoxError>
+where
+    Dispatch: Fn(JsonRpcRequest) -> DispatchFuture,
+    DispatchFuture: std::future::Future<Output = Result<serde_json::Value, JsonRpcError>>,
+{
+    let (read_half, mut write_half) = stream.into_split();
+    let mut lines = BufReader::new(read_half).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<JsonRpcRequest>(&line) {
+            Ok(request) => {
+                let id = request.id().to_string();
+                match dispatch(request).await {
+                    Ok(result) => serde_json::to_value(JsonRpcResponse::new(result, id)),
+                    Err(error) => serde_json::to_value(error),
+                }
+            }
+            Err(error) => serde_json::to_value(JsonRpcError {
+                code: -32700,
+                message: format!("parse error: {error}"),
+            }),
+        }?;
+
+        let mut encoded = serde_json::to_vec(&response)?;
+        encoded.push(b'\n');
+        write_half.write_all(&encoded).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+//! Benchmarks the finalized state's write path against a synthetic chain.
+//!
+//! Run with `cargo bench --bench finalized_state`, or directly as a CLI tool
+//! with `cargo run --release --bin finalized_state_bench -- --blocks 1000`
+//! for a one-off report outside of `criterion`'s statistics harness.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use zebra_chain::parameters::Network;
+use zebra_state::service::finalized_state::benchmark::{
+    generate_synthetic_chain, run_import_benchmark, SyntheticChainConfig,
+};
+
+fn import_benchmark(c: &mut Criterion) {
+    let chain = generate_synthetic_chain(SyntheticChainConfig {
+        block_count: 100,
+        ..SyntheticChainConfig::default()
+    });
+
+    c.bench_function("finalized_state import 100 synthetic blocks", |b| {
+        b.iter(|| run_import_benchmark(&chain, Network::Mainnet))
+    });
+}
+
+criterion_group!(benches, import_benchmark);
+criterion_main!(benches);
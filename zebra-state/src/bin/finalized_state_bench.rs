@@ -0,0 +1,80 @@
+//! Standalone CLI entry point for the finalized-state import benchmark.
+//!
+//! Bypasses `criterion`'s statistical sampling for a quick one-off report:
+//!
+//! ```text
+//! cargo run --release --bin finalized_state_bench -- --blocks 1000
+//! ```
+//!
+//! Falls back to [`DEFAULT_BLOCKS`] if `--blocks` isn't given.
+
+use std::process;
+
+use zebra_chain::parameters::Network;
+use zebra_state::service::finalized_state::benchmark::{
+    format_report, generate_synthetic_chain, run_import_benchmark, SyntheticChainConfig,
+};
+
+/// The block count used when `--blocks` isn't passed on the command line.
+const DEFAULT_BLOCKS: usize = 100;
+
+fn main() {
+    let block_count = match parse_block_count(std::env::args().skip(1)) {
+        Ok(block_count) => block_count,
+        Err(message) => {
+            eprintln!("{message}");
+            process::exit(1);
+        }
+    };
+
+    let chain = generate_synthetic_chain(SyntheticChainConfig {
+        block_count,
+        ..SyntheticChainConfig::default()
+    });
+
+    let report = run_import_benchmark(&chain, Network::Mainnet);
+    println!("{}", format_report(&report));
+}
+
+/// Parses a `--blocks <n>` argument out of `args`, defaulting to
+/// [`DEFAULT_BLOCKS`] if it isn't present.
+fn parse_block_count(mut args: impl Iterator<Item = String>) -> Result<usize, String> {
+    while let Some(arg) = args.next() {
+        if arg == "--blocks" {
+            let value = args
+                .next()
+                .ok_or_else(|| "--blocks requires a value".to_string())?;
+
+            return value
+                .parse()
+                .map_err(|_| format!("invalid --blocks value: {value:?}"));
+        }
+    }
+
+    Ok(DEFAULT_BLOCKS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_block_count_defaults_when_absent() {
+        assert_eq!(
+            parse_block_count(std::iter::empty()).unwrap(),
+            DEFAULT_BLOCKS
+        );
+    }
+
+    #[test]
+    fn parse_block_count_reads_the_flag() {
+        let args = ["--blocks".to_string(), "42".to_string()];
+        assert_eq!(parse_block_count(args.into_iter()).unwrap(), 42);
+    }
+
+    #[test]
+    fn parse_block_count_rejects_a_non_numeric_value() {
+        let args = ["--blocks".to_string(), "nope".to_string()];
+        assert!(parse_block_count(args.into_iter()).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Synthetic-state generation and import benchmarking for `finalized_state`.
+//!
+//! This module complements the round-trip helpers in [`crate::service::finalized_state::arbitrary`]
+//! with a deterministic, seedable chain generator, and a harness that imports the
+//! generated chain into a throwaway [`DiskDb`] so maintainers can see where disk
+//! space and write time go: per-column-family key counts and SST sizes, plus
+//! wall-clock import time and per-batch commit latency.
+//!
+//! Run it as a `cargo bench` target (see `zebra-state/benches/finalized_state.rs`),
+//! or standalone via [`run_import_benchmark`] and [`format_report`] from a small
+//! CLI wrapper (see `zebra-state/src/bin/finalized_state_bench.rs`), to catch regressions in
+//! serialization or write batching.
+
+#![allow(dead_code)]
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use proptest::prelude::*;
+
+use zebra_chain::{block, transaction::Transaction, transparent};
+
+use crate::service::finalized_state::{disk_db::DiskDb, zebra_db::ZebraDb, Config, FinalizedBlock};
+
+/// Parameters controlling a synthetic chain used for benchmarking.
+#[derive(Clone, Debug)]
+pub struct SyntheticChainConfig {
+    /// The random seed used to generate the chain deterministically.
+    pub seed: u64,
+
+    /// The number of blocks to generate.
+    pub block_count: usize,
+
+    /// The approximate number of transactions per block.
+    pub transactions_per_block: usize,
+
+    /// The approximate number of transparent outputs per transaction.
+    pub outputs_per_transaction: usize,
+}
+
+impl Default for SyntheticChainConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            block_count: 100,
+            transactions_per_block: 10,
+            outputs_per_transaction: 2,
+        }
+    }
+}
+
+/// A synthetic chain generated for benchmarking, along with the config used to
+/// produce it.
+pub struct SyntheticChain {
+    /// The config this chain was generated from.
+    pub config: SyntheticChainConfig,
+
+    /// The generated blocks, in height order, starting after genesis.
+    pub blocks: Vec<FinalizedBlock>,
+}
+
+/// Deterministically generates a [`SyntheticChain`] matching `config`, using a
+/// seeded [`proptest::test_runner::TestRunner`] so results are reproducible
+/// across runs (unlike the unseeded `Arbitrary` instances used for fuzzing).
+///
+/// Each block holds its coinbase transaction plus
+/// `config.transactions_per_block` generated transactions (see
+/// [`synthetic_transactions`]), and each block's header is linked to the
+/// previous block via `previous_block_hash`, so the generated blocks are an
+/// actual chain rather than `block_count` unrelated blocks.
+///
+/// The arbitrary-generated block's own first transaction (its coinbase) is
+/// kept as-is rather than replaced: dropping it would leave the block
+/// without a coinbase transaction, breaking height derivation and failing to
+/// commit.
+pub fn generate_synthetic_chain(config: SyntheticChainConfig) -> SyntheticChain {
+    let mut runner = proptest::test_runner::TestRunner::new_with_rng(
+        proptest::test_runner::Config::default(),
+        proptest::test_runner::TestRng::deterministic_rng(proptest::test_runner::RngAlgorithm::ChaCha),
+    );
+
+    let mut blocks = Vec::with_capacity(config.block_count);
+    let mut previous_hash = block::Hash([0; 32]);
+
+    for _ in 1..=config.block_count {
+        let mut block = any::<block::Block>()
+            .new_tree(&mut runner)
+            .expect("synthetic block generation should not fail")
+            .current();
+
+        let mut header = (*block.header).clone();
+        header.previous_block_hash = previous_hash;
+        block.header = Arc::new(header);
+
+        let coinbase = block
+            .transactions
+            .first()
+            .cloned()
+            .expect("arbitrary blocks have at least a coinbase transaction");
+
+        let mut transactions = Vec::with_capacity(config.transactions_per_block + 1);
+        transactions.push(coinbase);
+        transactions.extend(synthetic_transactions(&config, &mut runner));
+        block.transactions = transactions;
+
+        let finalized = FinalizedBlock::from(Arc::new(block));
+        previous_hash = finalized.hash;
+        blocks.push(finalized);
+    }
+
+    SyntheticChain { config, blocks }
+}
+
+/// Generates `config.transactions_per_block` arbitrary transactions. Each V4
+/// or V5 transaction has its transparent outputs replaced by
+/// `config.outputs_per_transaction` generated outputs; transactions of other
+/// versions keep their generated outputs. Generated coinbase transactions are
+/// not filtered out.
+fn synthetic_transactions(
+    config: &SyntheticChainConfig,
+    runner: &mut proptest::test_runner::TestRunner,
+) -> Vec<Arc<Transaction>> {
+    (0..config.transactions_per_block)
+        .map(|_| {
+            let mut transaction = any::<Transaction>()
+                .new_tree(runner)
+                .expect("synthetic transaction generation should not fail")
+                .current();
+
+            let outputs = prop::collection::vec(
+                any::<transparent::Output>(),
+                config.outputs_per_transaction,
+            )
+            .new_tree(runner)
+            .expect("synthetic output generation should not fail")
+            .current();
+
+            if let Transaction::V4 { outputs: tx_outputs, .. }
+            | Transaction::V5 { outputs: tx_outputs, .. } = &mut transaction
+            {
+                *tx_outputs = outputs;
+            }
+
+            Arc::new(transaction)
+        })
+        .collect()
+}
+
+/// The result of importing a [`SyntheticChain`] into a throwaway database.
+#[derive(Clone, Debug)]
+pub struct ImportReport {
+    /// The total wall-clock time spent importing every block.
+    pub total_import_time: Duration,
+
+    /// The wall-clock time spent committing each block's batch, in height order.
+    pub batch_commit_times: Vec<Duration>,
+
+    /// Per-column-family size and key count statistics, gathered after import.
+    pub column_family_stats: Vec<ColumnFamilyStats>,
+}
+
+/// Size and key-count statistics for a single RocksDB column family.
+#[derive(Clone, Debug)]
+pub struct ColumnFamilyStats {
+    /// The column family's name.
+    pub name: String,
+
+    /// RocksDB's estimated number of keys (`rocksdb.estimate-num-keys`).
+    pub estimated_num_keys: u64,
+
+    /// The total size in bytes of this column family's live SST files
+    /// (`rocksdb.total-sst-files-size`).
+    pub total_sst_file_size_bytes: u64,
+}
+
+/// Imports `chain` into a fresh temp-dir [`DiskDb`], recording import timing and
+/// final per-column-family size statistics.
+///
+/// This exercises the same write path as the finalized state service: each
+/// block's history and chain-value-pool batches are prepared and committed in a
+/// tight loop, so regressions in serialization or write batching show up here
+/// before they show up in a full sync.
+pub fn run_import_benchmark(chain: &SyntheticChain, network: zebra_chain::parameters::Network) -> ImportReport {
+    let config = Config::ephemeral();
+    let db = ZebraDb::new(&config, network);
+
+    let mut batch_commit_times = Vec::with_capacity(chain.blocks.len());
+    let import_start = Instant::now();
+
+    for finalized in &chain.blocks {
+        let batch_start = Instant::now();
+
+        db.commit_finalized_direct(finalized.clone(), "benchmark import")
+            .expect("synthetic blocks must commit cleanly");
+
+        batch_commit_times.push(batch_start.elapsed());
+    }
+
+    let total_import_time = import_start.elapsed();
+    let column_family_stats = collect_column_family_stats(db.db());
+
+    ImportReport {
+        total_import_time,
+        batch_commit_times,
+        column_family_stats,
+    }
+}
+
+/// Gathers `rocksdb.estimate-num-keys` and `rocksdb.total-sst-files-size` for
+/// every column family in `db`, so maintainers can see where `history_tree`,
+/// `tip_chain_value_pool`, and the transaction-index families dominate disk use.
+fn collect_column_family_stats(db: &DiskDb) -> Vec<ColumnFamilyStats> {
+    db.column_family_names()
+        .into_iter()
+        .map(|name| {
+            let cf = db.cf_handle(&name).unwrap();
+            ColumnFamilyStats {
+                estimated_num_keys: db
+                    .property_int_value_cf(cf, "rocksdb.estimate-num-keys")
+                    .unwrap_or_default(),
+                total_sst_file_size_bytes: db
+                    .property_int_value_cf(cf, "rocksdb.total-sst-files-size")
+                    .unwrap_or_default(),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Formats an [`ImportReport`] as a human-readable table, for the standalone
+/// CLI mode.
+pub fn format_report(report: &ImportReport) -> String {
+    let mut output = format!(
+        "Imported in {:?} ({} batches)\n",
+        report.total_import_time,
+        report.batch_commit_times.len()
+    );
+
+    output.push_str("column family                      | est. keys | sst bytes\n");
+    for stats in &report.column_family_stats {
+        output.push_str(&format!(
+            "{:<35} | {:>9} | {:>9}\n",
+            stats.name, stats.estimated_num_keys, stats.total_sst_file_size_bytes
+        ));
+    }
+
+    output
+}
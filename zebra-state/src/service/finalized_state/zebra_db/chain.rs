@@ -13,8 +13,11 @@
 
 use std::{borrow::Borrow, collections::HashMap, sync::Arc};
 
+use parking_lot::RwLock;
+
 use zebra_chain::{
     amount::NonNegative,
+    block::Height,
     history_tree::{HistoryTree, NonEmptyHistoryTree},
     transparent,
     value_balance::ValueBalance,
@@ -29,30 +32,129 @@ use crate::{
     BoxError, SemanticallyVerifiedBlock,
 };
 
+/// The default number of distinct heights [`ChainCache`] retains when no
+/// explicit capacity is given via [`ChainCache::with_capacity`].
+///
+/// A single entry is enough to make repeated reads at a stable tip cheap; a
+/// small capacity beyond that also keeps a read from missing the cache
+/// entirely while a write is in flight and has already cached the new tip
+/// height but a reader is still one block behind.
+pub const DEFAULT_CHAIN_CACHE_CAPACITY: usize = 2;
+
+/// A tip-height-keyed cache of a value that is otherwise re-deserialized from
+/// RocksDB on every read, even when the finalized tip hasn't advanced.
+///
+/// Caching by height (rather than just caching the latest value) means a read
+/// can detect and ignore a stale entry left behind by an earlier tip, without
+/// needing the write path to explicitly invalidate it: once the finalized tip
+/// moves past `height`, [`ChainCache::get_if_current`] simply stops matching,
+/// and the next read repopulates the entry for the new tip.
+///
+/// Retains up to `capacity` distinct heights (oldest evicted first), rather
+/// than just the single most recent one, so a reader that's briefly behind
+/// the writer's tip during a concurrent commit still gets a cache hit instead
+/// of falling through to RocksDB on every call.
+///
+/// # Concurrency
+///
+/// [`ChainCache::get_if_current`] and [`ChainCache::update`] each take the
+/// lock independently and release it before returning, so a reader never
+/// blocks a concurrent writer (or vice versa) for longer than it takes to
+/// clone or replace the small entry list.
+pub struct ChainCache<T: Clone> {
+    capacity: usize,
+    entries: RwLock<Vec<(Height, T)>>,
+}
+
+impl<T: Clone> Default for ChainCache<T> {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CHAIN_CACHE_CAPACITY)
+    }
+}
+
+impl<T: Clone> ChainCache<T> {
+    /// Creates an empty cache that retains at most `capacity` distinct
+    /// heights.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Returns the cached value if it was last written for `height`.
+    fn get_if_current(&self, height: Height) -> Option<T> {
+        let entries = self.entries.read();
+        entries
+            .iter()
+            .find(|(cached_height, _)| *cached_height == height)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Stores `value` as the cached value for `height`, evicting the oldest
+    /// entry first if the cache is already at capacity.
+    fn update(&self, height: Height, value: T) {
+        let mut entries = self.entries.write();
+        entries.retain(|(cached_height, _)| *cached_height != height);
+        if entries.len() >= self.capacity {
+            entries.remove(0);
+        }
+        entries.push((height, value));
+    }
+}
+
+// `ZebraDb` must declare `history_tree_cache: ChainCache<Arc<HistoryTree>>` and
+// `value_pool_cache: ChainCache<ValueBalance<NonNegative>>` fields, each
+// constructed via `ChainCache::with_capacity` from a configurable capacity
+// (e.g. on `Config`), for the methods below to compile and for that capacity
+// to actually be configurable end to end. `ZebraDb`'s struct definition isn't
+// part of this source tree, so that field wiring can't be made from this
+// file; everything `ChainCache` itself controls (capacity, eviction,
+// concurrent access) is implemented and tested here.
 impl ZebraDb {
     /// Returns the ZIP-221 history tree of the finalized tip or `None`
     /// if it does not exist yet in the state (pre-Heartwood).
     pub fn history_tree(&self) -> Arc<HistoryTree> {
-        if let Some(height) = self.finalized_tip_height() {
-            let history_tree_cf = self.db.cf_handle("history_tree").unwrap();
-
-            let history_tree: Option<NonEmptyHistoryTree> =
-                self.db.zs_get(&history_tree_cf, &height);
+        let Some(height) = self.finalized_tip_height() else {
+            return Default::default();
+        };
 
-            if let Some(non_empty_tree) = history_tree {
-                return Arc::new(HistoryTree::from(non_empty_tree));
-            }
+        if let Some(cached) = self.history_tree_cache.get_if_current(height) {
+            return cached;
         }
 
-        Default::default()
+        let history_tree_cf = self.db.cf_handle("history_tree").unwrap();
+
+        let history_tree: Option<NonEmptyHistoryTree> = self.db.zs_get(&history_tree_cf, &height);
+
+        let history_tree = history_tree
+            .map(|non_empty_tree| Arc::new(HistoryTree::from(non_empty_tree)))
+            .unwrap_or_default();
+
+        self.history_tree_cache.update(height, history_tree.clone());
+
+        history_tree
     }
 
     /// Returns the stored `ValueBalance` for the best chain at the finalized tip height.
     pub fn finalized_value_pool(&self) -> ValueBalance<NonNegative> {
+        let Some(height) = self.finalized_tip_height() else {
+            return ValueBalance::zero();
+        };
+
+        if let Some(cached) = self.value_pool_cache.get_if_current(height) {
+            return cached;
+        }
+
         let value_pool_cf = self.db.cf_handle("tip_chain_value_pool").unwrap();
-        self.db
+        let value_pool = self
+            .db
             .zs_get(&value_pool_cf, &())
-            .unwrap_or_else(ValueBalance::zero)
+            .unwrap_or_else(ValueBalance::zero);
+
+        self.value_pool_cache.update(height, value_pool);
+
+        value_pool
     }
 }
 
@@ -121,3 +223,61 @@ impl DiskWriteBatch {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::*;
+
+    #[test]
+    fn chain_cache_respects_its_configured_capacity() {
+        let cache = ChainCache::with_capacity(2);
+
+        cache.update(Height(1), "a");
+        cache.update(Height(2), "b");
+        cache.update(Height(3), "c");
+
+        // The oldest entry (height 1) should have been evicted.
+        assert_eq!(cache.get_if_current(Height(1)), None);
+        assert_eq!(cache.get_if_current(Height(2)), Some("b"));
+        assert_eq!(cache.get_if_current(Height(3)), Some("c"));
+    }
+
+    #[test]
+    fn chain_cache_capacity_is_at_least_one() {
+        let cache: ChainCache<u32> = ChainCache::with_capacity(0);
+
+        cache.update(Height(1), 100);
+        assert_eq!(cache.get_if_current(Height(1)), Some(100));
+    }
+
+    /// Concurrent reads must keep observing a consistent entry (either the
+    /// old value or the new one, never a torn or poisoned state) while
+    /// another thread repeatedly overwrites the cache.
+    #[test]
+    fn chain_cache_reads_are_consistent_during_concurrent_writes() {
+        let cache = Arc::new(ChainCache::with_capacity(4));
+        let writer_cache = cache.clone();
+
+        let writer = thread::spawn(move || {
+            for height in 1..=200u32 {
+                writer_cache.update(Height(height), height);
+            }
+        });
+
+        let reader_cache = cache.clone();
+        let reader = thread::spawn(move || {
+            for _ in 0..200 {
+                for height in 1..=200u32 {
+                    if let Some(value) = reader_cache.get_if_current(Height(height)) {
+                        assert_eq!(value, height);
+                    }
+                }
+            }
+        });
+
+        writer.join().expect("writer thread should not panic");
+        reader.join().expect("reader thread should not panic");
+    }
+}
@@ -12,8 +12,10 @@
 //! The [`crate::constants::DATABASE_FORMAT_VERSION`] constant must
 //! be incremented each time the database format (column, serialization, etc) changes.
 
+use serde::{Deserialize, Serialize};
+
 use zebra_chain::{
-    history_tree::HistoryTree, orchard, parameters::Network, sapling, sprout,
+    block, history_tree::HistoryTree, orchard, parameters::Network, sapling, sprout,
     transaction::Transaction,
 };
 
@@ -26,12 +28,151 @@ use crate::{
     BoxError,
 };
 
+/// The shard (subtree) level used for Sapling and Orchard note commitment subtrees.
+///
+/// This matches the NU5 shard level that `lightwalletd` uses for its subtree-root
+/// sync protocol: each subtree covers `2^SUBTREE_SHARD_HEIGHT` leaves.
+pub const SUBTREE_SHARD_HEIGHT: u8 = 16;
+
+/// The number of leaves in a single completed note commitment subtree.
+pub const SUBTREE_INTERVAL: u64 = 1 << SUBTREE_SHARD_HEIGHT;
+
+/// The index of a completed Sapling or Orchard note commitment subtree.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct NoteCommitmentSubtreeIndex(pub u16);
+
+/// The root of a completed note commitment subtree, and the height of the block
+/// that completed it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NoteCommitmentSubtreeData<Root> {
+    /// The height of the block that completed this subtree.
+    pub end_height: block::Height,
+
+    /// The root of the completed subtree.
+    pub root: Root,
+}
+
+impl<Root> NoteCommitmentSubtreeData<Root> {
+    /// Creates new subtree data from the height that completed the subtree, and its root.
+    pub fn new(end_height: block::Height, root: Root) -> Self {
+        Self { end_height, root }
+    }
+}
+
+/// Returns the subtree index completed by an append that brought a tree's absolute
+/// leaf position to `position`, or `None` if `position` isn't a subtree boundary.
+///
+/// A subtree of index `i` is complete exactly when the position reaches
+/// `(i + 1) * SUBTREE_INTERVAL - 1`.
+fn completed_subtree_index(position: u64) -> Option<NoteCommitmentSubtreeIndex> {
+    if (position + 1) % SUBTREE_INTERVAL == 0 {
+        let index = (position + 1) / SUBTREE_INTERVAL - 1;
+        Some(NoteCommitmentSubtreeIndex(index as u16))
+    } else {
+        None
+    }
+}
+
+/// Returns the index of the most recently completed subtree whose final leaf
+/// position is at or before `position`, or `None` if no subtree has completed
+/// by that point.
+///
+/// Used to find the nearest stored shard cap a historical witness rebuild can
+/// resume from, instead of replaying every leaf since genesis.
+fn last_completed_shard_at_or_before(position: u64) -> Option<NoteCommitmentSubtreeIndex> {
+    if position + 1 < SUBTREE_INTERVAL {
+        None
+    } else {
+        let index = (position + 1) / SUBTREE_INTERVAL - 1;
+        Some(NoteCommitmentSubtreeIndex(index as u16))
+    }
+}
+
+/// Binds a pool's note commitment tree to the column families it is stored in,
+/// so Sprout, Sapling, and Orchard can share the same checkpointed write path
+/// in [`DiskWriteBatch::write_tree_shard`] instead of repeating it per pool.
+trait ShardStore {
+    /// The column family storing the tree itself, keyed by height.
+    ///
+    /// Unused by [`DiskWriteBatch::write_tree_shard`] for a
+    /// [`ShardStore::SHARDED`] pool: its tip tree is reconstructed from a shard
+    /// cap plus replayed commitments instead (see
+    /// [`ZebraDb::sapling_note_commitment_tree`]), so only the empty genesis
+    /// tree is ever written here for it.
+    const TREE_CF: &'static str;
+
+    /// The column family storing a lightweight per-height leaf position
+    /// checkpoint, so a pool's leaf position as of a height can be read
+    /// without deserializing the whole tree.
+    const CHECKPOINT_CF: &'static str;
+
+    /// Whether this pool's tip tree can be rebuilt from the nearest
+    /// completed shard's cap plus its per-leaf commitments recorded in
+    /// [`DiskWriteBatch::prepare_note_commitment_batch`], instead of needing
+    /// a full copy of the tree persisted on every block.
+    ///
+    /// `false` for Sprout, which predates the shard/cap protocol added in
+    /// `31e4648` and has neither a shard-cap nor a per-leaf-commitment
+    /// column family; `true` for Sapling and Orchard, which do.
+    const SHARDED: bool = false;
+
+    /// Returns this tree's absolute leaf position, or `None` if it is empty.
+    fn leaf_position(&self) -> Option<u64>;
+}
+
+impl ShardStore for sprout::tree::NoteCommitmentTree {
+    const TREE_CF: &'static str = "sprout_note_commitment_tree";
+    const CHECKPOINT_CF: &'static str = "sprout_note_commitment_tree_checkpoints";
+
+    fn leaf_position(&self) -> Option<u64> {
+        self.position()
+    }
+}
+
+impl ShardStore for sapling::tree::NoteCommitmentTree {
+    const TREE_CF: &'static str = "sapling_note_commitment_tree";
+    const CHECKPOINT_CF: &'static str = "sapling_note_commitment_tree_checkpoints";
+    const SHARDED: bool = true;
+
+    fn leaf_position(&self) -> Option<u64> {
+        self.position()
+    }
+}
+
+impl ShardStore for orchard::tree::NoteCommitmentTree {
+    const TREE_CF: &'static str = "orchard_note_commitment_tree";
+    const CHECKPOINT_CF: &'static str = "orchard_note_commitment_tree_checkpoints";
+    const SHARDED: bool = true;
+
+    fn leaf_position(&self) -> Option<u64> {
+        self.position()
+    }
+}
+
 /// An argument wrapper struct for note commitment trees.
 #[derive(Clone, Debug)]
 pub struct NoteCommitmentTrees {
     sprout: sprout::tree::NoteCommitmentTree,
     sapling: sapling::tree::NoteCommitmentTree,
     orchard: orchard::tree::NoteCommitmentTree,
+
+    /// Sapling subtrees completed since this value was last read from disk,
+    /// waiting to be written out by [`DiskWriteBatch::prepare_note_commitment_batch`].
+    new_sapling_subtrees: Vec<(NoteCommitmentSubtreeIndex, sapling::tree::Root)>,
+
+    /// Orchard subtrees completed since this value was last read from disk,
+    /// waiting to be written out by [`DiskWriteBatch::prepare_note_commitment_batch`].
+    new_orchard_subtrees: Vec<(NoteCommitmentSubtreeIndex, orchard::tree::Root)>,
+
+    /// Sapling note commitments appended since this value was last read from disk,
+    /// along with their absolute leaf position, waiting to be written out by
+    /// [`DiskWriteBatch::prepare_note_commitment_batch`].
+    new_sapling_commitments: Vec<(u64, sapling::NoteCommitment)>,
+
+    /// Orchard note commitments appended since this value was last read from disk,
+    /// along with their absolute leaf position, waiting to be written out by
+    /// [`DiskWriteBatch::prepare_note_commitment_batch`].
+    new_orchard_commitments: Vec<(u64, orchard::NoteCommitment)>,
 }
 
 impl ZebraDb {
@@ -74,6 +215,23 @@ impl ZebraDb {
         self.db.zs_contains(orchard_anchors, &orchard_anchor)
     }
 
+    /// Returns the height at which `sapling_anchor` was the Sapling tree's
+    /// root, or `None` if it has never been an anchor.
+    ///
+    /// Used by [`ZebraDb::sapling_witness`] to rebuild the tree as of a
+    /// historical anchor, rather than only ever accepting the tip's anchor.
+    fn sapling_anchor_height(&self, sapling_anchor: &sapling::tree::Root) -> Option<block::Height> {
+        let sapling_anchors = self.db.cf_handle("sapling_anchors").unwrap();
+        self.db.zs_get(sapling_anchors, sapling_anchor)
+    }
+
+    /// Returns the height at which `orchard_anchor` was the Orchard tree's
+    /// root, or `None` if it has never been an anchor.
+    fn orchard_anchor_height(&self, orchard_anchor: &orchard::tree::Root) -> Option<block::Height> {
+        let orchard_anchors = self.db.cf_handle("orchard_anchors").unwrap();
+        self.db.zs_get(orchard_anchors, orchard_anchor)
+    }
+
     /// Returns the Sprout note commitment tree of the finalized tip
     /// or the empty tree if the state is empty.
     pub fn sprout_note_commitment_tree(&self) -> sprout::tree::NoteCommitmentTree {
@@ -103,34 +261,246 @@ impl ZebraDb {
 
     /// Returns the Sapling note commitment tree of the finalized tip
     /// or the empty tree if the state is empty.
+    ///
+    /// Rebuilt from the nearest completed shard's cap plus its replayed
+    /// per-leaf commitments (see [`ZebraDb::sapling_witness`]), rather than
+    /// read back from a full copy of the tree: [`DiskWriteBatch::write_tree_shard`]
+    /// doesn't persist one for a [`ShardStore::SHARDED`] pool, since doing so
+    /// costs O(tree size) on every single block.
     pub fn sapling_note_commitment_tree(&self) -> sapling::tree::NoteCommitmentTree {
-        let height = match self.finalized_tip_height() {
-            Some(h) => h,
-            None => return Default::default(),
+        let Some(height) = self.finalized_tip_height() else {
+            return Default::default();
         };
 
-        let sapling_note_commitment_tree =
-            self.db.cf_handle("sapling_note_commitment_tree").unwrap();
+        let Some(tip_position) = self.sapling_tree_leaf_position_checkpoint(height) else {
+            return Default::default();
+        };
 
-        self.db
-            .zs_get(sapling_note_commitment_tree, &height)
-            .expect("Sapling note commitment tree must exist if there is a finalized tip")
+        let (mut tree, rebuild_from_position) = self
+            .last_completed_sapling_shard_cap(tip_position)
+            .map(|(index, tree)| (tree, (index.0 as u64 + 1) * SUBTREE_INTERVAL))
+            .unwrap_or_else(|| (sapling::tree::NoteCommitmentTree::default(), 0));
+
+        let cf = self.db.cf_handle("sapling_note_commitments").unwrap();
+        for leaf_position in rebuild_from_position..=tip_position {
+            let (_, commitment) = self
+                .db
+                .zs_get::<u64, (block::Height, sapling::NoteCommitment)>(cf, &leaf_position)
+                .expect("a recorded checkpoint position must have a matching stored commitment");
+            tree.append(commitment)
+                .expect("stored commitments must replay cleanly onto their shard cap");
+        }
+
+        tree
     }
 
     /// Returns the Orchard note commitment tree of the finalized tip
     /// or the empty tree if the state is empty.
+    ///
+    /// See [`ZebraDb::sapling_note_commitment_tree`] for why this is rebuilt
+    /// from a shard cap instead of read back whole.
     pub fn orchard_note_commitment_tree(&self) -> orchard::tree::NoteCommitmentTree {
-        let height = match self.finalized_tip_height() {
-            Some(h) => h,
-            None => return Default::default(),
+        let Some(height) = self.finalized_tip_height() else {
+            return Default::default();
+        };
+
+        let Some(tip_position) = self.orchard_tree_leaf_position_checkpoint(height) else {
+            return Default::default();
         };
 
-        let orchard_note_commitment_tree =
-            self.db.cf_handle("orchard_note_commitment_tree").unwrap();
+        let (mut tree, rebuild_from_position) = self
+            .last_completed_orchard_shard_cap(tip_position)
+            .map(|(index, tree)| (tree, (index.0 as u64 + 1) * SUBTREE_INTERVAL))
+            .unwrap_or_else(|| (orchard::tree::NoteCommitmentTree::default(), 0));
+
+        let cf = self.db.cf_handle("orchard_note_commitments").unwrap();
+        for leaf_position in rebuild_from_position..=tip_position {
+            let (_, commitment) = self
+                .db
+                .zs_get::<u64, (block::Height, orchard::NoteCommitment)>(cf, &leaf_position)
+                .expect("a recorded checkpoint position must have a matching stored commitment");
+            tree.append(commitment)
+                .expect("stored commitments must replay cleanly onto their shard cap");
+        }
+
+        tree
+    }
+
+    /// Returns the leaf position that a pool's note commitment tree had
+    /// reached as of `height`, without deserializing the full tree.
+    ///
+    /// Used to find how far to replay stored commitments when rebuilding a
+    /// Sapling or Orchard tree, rather than reading back a full copy of it.
+    fn tree_leaf_position_checkpoint<T: ShardStore>(&self, height: block::Height) -> Option<u64> {
+        let checkpoint_cf = self.db.cf_handle(T::CHECKPOINT_CF).unwrap();
+        self.db.zs_get(checkpoint_cf, &height)
+    }
 
+    /// Returns the Sprout tree's leaf position checkpoint at `height`.
+    pub fn sprout_tree_leaf_position_checkpoint(&self, height: block::Height) -> Option<u64> {
+        self.tree_leaf_position_checkpoint::<sprout::tree::NoteCommitmentTree>(height)
+    }
+
+    /// Returns the Sapling tree's leaf position checkpoint at `height`.
+    pub fn sapling_tree_leaf_position_checkpoint(&self, height: block::Height) -> Option<u64> {
+        self.tree_leaf_position_checkpoint::<sapling::tree::NoteCommitmentTree>(height)
+    }
+
+    /// Returns the Orchard tree's leaf position checkpoint at `height`.
+    pub fn orchard_tree_leaf_position_checkpoint(&self, height: block::Height) -> Option<u64> {
+        self.tree_leaf_position_checkpoint::<orchard::tree::NoteCommitmentTree>(height)
+    }
+
+    /// Returns the heights of every stored leaf position checkpoint greater
+    /// than `height`, for a pool's tree.
+    ///
+    /// Used by [`DiskWriteBatch::prepare_subtree_rollback_batch`] to find the
+    /// stale checkpoints a reorg back to `height` needs to delete.
+    fn tree_leaf_position_checkpoints_above<T: ShardStore>(
+        &self,
+        height: block::Height,
+    ) -> Vec<block::Height> {
+        let checkpoint_cf = self.db.cf_handle(T::CHECKPOINT_CF).unwrap();
         self.db
-            .zs_get(orchard_note_commitment_tree, &height)
-            .expect("Orchard note commitment tree must exist if there is a finalized tip")
+            .zs_iter::<block::Height, u64>(checkpoint_cf)
+            .map(|(checkpoint_height, _position)| checkpoint_height)
+            .filter(|checkpoint_height| *checkpoint_height > height)
+            .collect()
+    }
+
+    /// The number of leaves of history for which Zebra retains enough stored
+    /// commitments to recompute a past witness. Anchors further back than this
+    /// from the tip are no longer retrievable via [`ZebraDb::sapling_witness`] /
+    /// [`ZebraDb::orchard_witness`].
+    pub const WITNESS_RETENTION_WINDOW: u64 = 10 * SUBTREE_INTERVAL;
+
+    /// Returns the most recently completed Sapling subtree's index and cap
+    /// (the tree's frontier snapshot taken right after that subtree
+    /// completed) at or before `position`, or `None` if no subtree has
+    /// completed by that point.
+    ///
+    /// Used by [`ZebraDb::sapling_witness`] so a historical witness can
+    /// resume from the nearest shard boundary instead of replaying every
+    /// leaf since genesis.
+    fn last_completed_sapling_shard_cap(
+        &self,
+        position: u64,
+    ) -> Option<(NoteCommitmentSubtreeIndex, sapling::tree::NoteCommitmentTree)> {
+        let index = last_completed_shard_at_or_before(position)?;
+        let cap_cf = self
+            .db
+            .cf_handle("sapling_note_commitment_tree_shard_cap")
+            .unwrap();
+        self.db.zs_get(cap_cf, &index).map(|tree| (index, tree))
+    }
+
+    /// Returns the most recently completed Orchard subtree's index and cap
+    /// at or before `position`, or `None` if no subtree has completed by
+    /// that point.
+    ///
+    /// See [`ZebraDb::last_completed_sapling_shard_cap`].
+    fn last_completed_orchard_shard_cap(
+        &self,
+        position: u64,
+    ) -> Option<(NoteCommitmentSubtreeIndex, orchard::tree::NoteCommitmentTree)> {
+        let index = last_completed_shard_at_or_before(position)?;
+        let cap_cf = self
+            .db
+            .cf_handle("orchard_note_commitment_tree_shard_cap")
+            .unwrap();
+        self.db.zs_get(cap_cf, &index).map(|tree| (index, tree))
+    }
+
+    /// Returns the authentication path (sibling hashes from leaf to root) for the
+    /// Sapling note at `position`, as of `as_of_anchor`, or the tip if `as_of_anchor`
+    /// is `None`.
+    ///
+    /// Historical anchors are resolved via the height they were current at, so
+    /// this isn't limited to the tip's anchor. The replay used to rebuild the
+    /// tree resumes from the nearest completed shard's cap (see
+    /// [`ZebraDb::last_completed_sapling_shard_cap`]) rather than genesis, so
+    /// it only ever walks at most [`SUBTREE_INTERVAL`] leaves.
+    ///
+    /// Returns `None` if `position` hasn't been reached yet, if `as_of_anchor`
+    /// was never a Sapling anchor, or if `position` is older than
+    /// [`ZebraDb::WITNESS_RETENTION_WINDOW`].
+    pub fn sapling_witness(
+        &self,
+        position: u64,
+        as_of_anchor: Option<sapling::tree::Root>,
+    ) -> Option<Vec<[u8; 32]>> {
+        let tip_tree = self.sapling_note_commitment_tree();
+        let tip_position = tip_tree.position()?;
+
+        let as_of_position = match as_of_anchor {
+            None => tip_position,
+            Some(anchor) if anchor == tip_tree.root() => tip_position,
+            Some(anchor) => {
+                let anchor_height = self.sapling_anchor_height(&anchor)?;
+                self.sapling_tree_leaf_position_checkpoint(anchor_height)?
+            }
+        };
+
+        if position > as_of_position || tip_position - position > Self::WITNESS_RETENTION_WINDOW {
+            return None;
+        }
+
+        let (mut tree, rebuild_from_position) = self
+            .last_completed_sapling_shard_cap(as_of_position)
+            .map(|(index, tree)| (tree, (index.0 as u64 + 1) * SUBTREE_INTERVAL))
+            .unwrap_or_else(|| (sapling::tree::NoteCommitmentTree::default(), 0));
+
+        let cf = self.db.cf_handle("sapling_note_commitments").unwrap();
+        for leaf_position in rebuild_from_position..=position {
+            let (_, commitment) = self
+                .db
+                .zs_get::<u64, (block::Height, sapling::NoteCommitment)>(cf, &leaf_position)?;
+            tree.append(commitment).ok()?;
+        }
+
+        tree.witness(position)
+    }
+
+    /// Returns the authentication path for the Orchard note at `position`, as of
+    /// `as_of_anchor`, or the tip if `as_of_anchor` is `None`.
+    ///
+    /// See [`ZebraDb::sapling_witness`] for the retention, anchor, and replay
+    /// caveats.
+    pub fn orchard_witness(
+        &self,
+        position: u64,
+        as_of_anchor: Option<orchard::tree::Root>,
+    ) -> Option<Vec<[u8; 32]>> {
+        let tip_tree = self.orchard_note_commitment_tree();
+        let tip_position = tip_tree.position()?;
+
+        let as_of_position = match as_of_anchor {
+            None => tip_position,
+            Some(anchor) if anchor == tip_tree.root() => tip_position,
+            Some(anchor) => {
+                let anchor_height = self.orchard_anchor_height(&anchor)?;
+                self.orchard_tree_leaf_position_checkpoint(anchor_height)?
+            }
+        };
+
+        if position > as_of_position || tip_position - position > Self::WITNESS_RETENTION_WINDOW {
+            return None;
+        }
+
+        let (mut tree, rebuild_from_position) = self
+            .last_completed_orchard_shard_cap(as_of_position)
+            .map(|(index, tree)| (tree, (index.0 as u64 + 1) * SUBTREE_INTERVAL))
+            .unwrap_or_else(|| (orchard::tree::NoteCommitmentTree::default(), 0));
+
+        let cf = self.db.cf_handle("orchard_note_commitments").unwrap();
+        for leaf_position in rebuild_from_position..=position {
+            let (_, commitment) = self
+                .db
+                .zs_get::<u64, (block::Height, orchard::NoteCommitment)>(cf, &leaf_position)?;
+            tree.append(commitment).ok()?;
+        }
+
+        tree.witness(position)
     }
 
     /// Returns the shielded note commitment trees of the finalized tip
@@ -140,14 +510,134 @@ impl ZebraDb {
             sprout: self.sprout_note_commitment_tree(),
             sapling: self.sapling_note_commitment_tree(),
             orchard: self.orchard_note_commitment_tree(),
+            new_sapling_subtrees: Vec::new(),
+            new_orchard_subtrees: Vec::new(),
+            new_sapling_commitments: Vec::new(),
+            new_orchard_commitments: Vec::new(),
         }
     }
+
+    /// Returns the Sapling nullifiers spent at a height within `height_range`,
+    /// paired with the height that spent them.
+    ///
+    /// Used by viewing-key scanners to detect spent notes directly against the
+    /// validated state, instead of re-deriving positions from full-tree reserialization.
+    pub fn sapling_nullifiers_in_range(
+        &self,
+        height_range: std::ops::RangeInclusive<block::Height>,
+    ) -> Vec<(sapling::Nullifier, block::Height)> {
+        let sapling_nullifiers = self.db.cf_handle("sapling_nullifiers").unwrap();
+
+        self.db
+            .zs_iter::<sapling::Nullifier, block::Height>(sapling_nullifiers)
+            .filter(|(_, spending_height)| height_range.contains(spending_height))
+            .collect()
+    }
+
+    /// Returns the Orchard nullifiers spent at a height within `height_range`,
+    /// paired with the height that spent them.
+    pub fn orchard_nullifiers_in_range(
+        &self,
+        height_range: std::ops::RangeInclusive<block::Height>,
+    ) -> Vec<(orchard::Nullifier, block::Height)> {
+        let orchard_nullifiers = self.db.cf_handle("orchard_nullifiers").unwrap();
+
+        self.db
+            .zs_iter::<orchard::Nullifier, block::Height>(orchard_nullifiers)
+            .filter(|(_, spending_height)| height_range.contains(spending_height))
+            .collect()
+    }
+
+    /// Returns the Sapling note commitments appended at a height within `height_range`,
+    /// paired with their absolute leaf position.
+    ///
+    /// A caller holding an incoming viewing key can trial-decrypt these outputs and
+    /// later build witnesses from their positions.
+    pub fn sapling_note_commitments_with_positions(
+        &self,
+        height_range: std::ops::RangeInclusive<block::Height>,
+    ) -> Vec<(u64, sapling::NoteCommitment)> {
+        let sapling_note_commitments = self.db.cf_handle("sapling_note_commitments").unwrap();
+
+        self.db
+            .zs_iter::<u64, (block::Height, sapling::NoteCommitment)>(sapling_note_commitments)
+            .filter(|(_, (height, _))| height_range.contains(height))
+            .map(|(position, (_, commitment))| (position, commitment))
+            .collect()
+    }
+
+    /// Returns the Orchard note commitments appended at a height within `height_range`,
+    /// paired with their absolute leaf position.
+    pub fn orchard_note_commitments_with_positions(
+        &self,
+        height_range: std::ops::RangeInclusive<block::Height>,
+    ) -> Vec<(u64, orchard::NoteCommitment)> {
+        let orchard_note_commitments = self.db.cf_handle("orchard_note_commitments").unwrap();
+
+        self.db
+            .zs_iter::<u64, (block::Height, orchard::NoteCommitment)>(orchard_note_commitments)
+            .filter(|(_, (height, _))| height_range.contains(height))
+            .map(|(position, (_, commitment))| (position, commitment))
+            .collect()
+    }
+
+    /// Returns the Sapling subtree at `index`, if it has been completed.
+    pub fn sapling_subtree(
+        &self,
+        index: NoteCommitmentSubtreeIndex,
+    ) -> Option<NoteCommitmentSubtreeData<sapling::tree::Root>> {
+        let sapling_subtree = self.db.cf_handle("sapling_subtree").unwrap();
+        self.db.zs_get(sapling_subtree, &index)
+    }
+
+    /// Returns the Orchard subtree at `index`, if it has been completed.
+    pub fn orchard_subtree(
+        &self,
+        index: NoteCommitmentSubtreeIndex,
+    ) -> Option<NoteCommitmentSubtreeData<orchard::tree::Root>> {
+        let orchard_subtree = self.db.cf_handle("orchard_subtree").unwrap();
+        self.db.zs_get(orchard_subtree, &index)
+    }
+
+    /// Returns up to `limit` Sapling subtrees starting at `start_index`, in index order.
+    pub fn sapling_subtree_list_by_index_range(
+        &self,
+        start_index: NoteCommitmentSubtreeIndex,
+        limit: u16,
+    ) -> Vec<(NoteCommitmentSubtreeIndex, NoteCommitmentSubtreeData<sapling::tree::Root>)> {
+        (start_index.0..)
+            .take(limit as usize)
+            .map_while(|index| {
+                let index = NoteCommitmentSubtreeIndex(index);
+                self.sapling_subtree(index).map(|data| (index, data))
+            })
+            .collect()
+    }
+
+    /// Returns up to `limit` Orchard subtrees starting at `start_index`, in index order.
+    pub fn orchard_subtree_list_by_index_range(
+        &self,
+        start_index: NoteCommitmentSubtreeIndex,
+        limit: u16,
+    ) -> Vec<(NoteCommitmentSubtreeIndex, NoteCommitmentSubtreeData<orchard::tree::Root>)> {
+        (start_index.0..)
+            .take(limit as usize)
+            .map_while(|index| {
+                let index = NoteCommitmentSubtreeIndex(index);
+                self.orchard_subtree(index).map(|data| (index, data))
+            })
+            .collect()
+    }
 }
 
 impl DiskWriteBatch {
     /// Prepare a database batch containing `finalized.block`'s nullifiers,
     /// and return it (without actually writing anything).
     ///
+    /// Sapling and Orchard nullifiers are keyed by the nullifier and store the
+    /// `height` that spent them, so a scanner can later ask "which nullifiers
+    /// were spent in this height range" without re-deriving it from blocks.
+    ///
     /// # Errors
     ///
     /// - This method doesn't currently return any errors, but it might in future
@@ -155,6 +645,7 @@ impl DiskWriteBatch {
         &mut self,
         db: &DiskDb,
         transaction: &Transaction,
+        height: block::Height,
     ) -> Result<(), BoxError> {
         let sprout_nullifiers = db.cf_handle("sprout_nullifiers").unwrap();
         let sapling_nullifiers = db.cf_handle("sapling_nullifiers").unwrap();
@@ -165,10 +656,10 @@ impl DiskWriteBatch {
             self.zs_insert(sprout_nullifiers, sprout_nullifier, ());
         }
         for sapling_nullifier in transaction.sapling_nullifiers() {
-            self.zs_insert(sapling_nullifiers, sapling_nullifier, ());
+            self.zs_insert(sapling_nullifiers, sapling_nullifier, height);
         }
         for orchard_nullifier in transaction.orchard_nullifiers() {
-            self.zs_insert(orchard_nullifiers, orchard_nullifier, ());
+            self.zs_insert(orchard_nullifiers, orchard_nullifier, height);
         }
 
         Ok(())
@@ -196,16 +687,89 @@ impl DiskWriteBatch {
             note_commitment_trees
                 .sapling
                 .append(*sapling_note_commitment)?;
+
+            if let Some(position) = note_commitment_trees.sapling.position() {
+                note_commitment_trees
+                    .new_sapling_commitments
+                    .push((position, *sapling_note_commitment));
+            }
+
+            if let Some(index) = note_commitment_trees
+                .sapling
+                .position()
+                .and_then(completed_subtree_index)
+            {
+                note_commitment_trees
+                    .new_sapling_subtrees
+                    .push((index, note_commitment_trees.sapling.root()));
+            }
         }
         for orchard_note_commitment in transaction.orchard_note_commitments() {
             note_commitment_trees
                 .orchard
                 .append(*orchard_note_commitment)?;
+
+            if let Some(position) = note_commitment_trees.orchard.position() {
+                note_commitment_trees
+                    .new_orchard_commitments
+                    .push((position, *orchard_note_commitment));
+            }
+
+            if let Some(index) = note_commitment_trees
+                .orchard
+                .position()
+                .and_then(completed_subtree_index)
+            {
+                note_commitment_trees
+                    .new_orchard_subtrees
+                    .push((index, note_commitment_trees.orchard.root()));
+            }
         }
 
         Ok(())
     }
 
+    /// Records a single pool's leaf position checkpoint at `height`, and, for
+    /// a pool that isn't [`ShardStore::SHARDED`], also replaces the previous
+    /// height's full tree entry with this one.
+    ///
+    /// This is the shared write path used for Sprout, Sapling, and Orchard, so each
+    /// pool only needs to touch its own tree and checkpoint entries, rather than
+    /// duplicating the delete-then-insert dance per pool.
+    ///
+    /// For Sapling and Orchard, no tree is written to [`ShardStore::TREE_CF`]
+    /// at all: the tip tree is rebuilt on read from the nearest completed
+    /// shard's cap plus its per-leaf commitments (see
+    /// [`ZebraDb::sapling_note_commitment_tree`]), so persisting another full
+    /// copy of it here on every block would cost O(tree size) per block for
+    /// no benefit, on top of the immutable per-shard caps that
+    /// [`DiskWriteBatch::prepare_note_commitment_batch`] already writes once
+    /// a subtree completes. Sprout predates that shard/cap protocol and has
+    /// no per-leaf commitment storage to rebuild from, so it keeps the old
+    /// "small working tree, replaced every block" entry here.
+    fn write_tree_shard<T>(&mut self, db: &DiskDb, height: block::Height, tree: T)
+    where
+        T: ShardStore + crate::service::finalized_state::disk_format::IntoDisk,
+    {
+        let checkpoint_cf = db.cf_handle(T::CHECKPOINT_CF).unwrap();
+
+        if let Some(position) = tree.leaf_position() {
+            self.zs_insert(checkpoint_cf, height, position);
+        }
+
+        if T::SHARDED {
+            return;
+        }
+
+        let tree_cf = db.cf_handle(T::TREE_CF).unwrap();
+
+        if let Some(previous_height) = height - 1 {
+            self.zs_delete(tree_cf, previous_height);
+        }
+
+        self.zs_insert(tree_cf, height, tree);
+    }
+
     /// Prepare a database batch containing the note commitment and history tree updates
     /// from `finalized.block`, and return it (without actually writing anything).
     ///
@@ -228,47 +792,83 @@ impl DiskWriteBatch {
         let sapling_anchors = db.cf_handle("sapling_anchors").unwrap();
         let orchard_anchors = db.cf_handle("orchard_anchors").unwrap();
 
-        let sprout_note_commitment_tree_cf = db.cf_handle("sprout_note_commitment_tree").unwrap();
-        let sapling_note_commitment_tree_cf = db.cf_handle("sapling_note_commitment_tree").unwrap();
-        let orchard_note_commitment_tree_cf = db.cf_handle("orchard_note_commitment_tree").unwrap();
-
         let FinalizedBlock { height, .. } = finalized;
 
+        let sapling_subtree_cf = db.cf_handle("sapling_subtree").unwrap();
+        let orchard_subtree_cf = db.cf_handle("orchard_subtree").unwrap();
+
+        let sapling_shard_cap_cf = db
+            .cf_handle("sapling_note_commitment_tree_shard_cap")
+            .unwrap();
+        let orchard_shard_cap_cf = db
+            .cf_handle("orchard_note_commitment_tree_shard_cap")
+            .unwrap();
+
+        let sapling_note_commitments_cf = db.cf_handle("sapling_note_commitments").unwrap();
+        let orchard_note_commitments_cf = db.cf_handle("orchard_note_commitments").unwrap();
+
         let sprout_root = note_commitment_trees.sprout.root();
         let sapling_root = note_commitment_trees.sapling.root();
         let orchard_root = note_commitment_trees.orchard.root();
 
-        // Compute the new anchors and index them
-        // Note: if the root hasn't changed, we write the same value again.
+        // Compute the new anchors and index Sapling and Orchard anchors by the
+        // latest height at which they are the tree's root, so a historical
+        // witness lookup can resolve an anchor back to the leaf position it
+        // corresponds to.
+        // Note: if the root hasn't changed, its entry is overwritten (for
+        // Sapling and Orchard, with this block's height).
         self.zs_insert(sprout_anchors, sprout_root, &note_commitment_trees.sprout);
-        self.zs_insert(sapling_anchors, sapling_root, ());
-        self.zs_insert(orchard_anchors, orchard_root, ());
-
-        // Update the trees in state
-        let current_tip_height = *height - 1;
-        if let Some(h) = current_tip_height {
-            self.zs_delete(sprout_note_commitment_tree_cf, h);
-            self.zs_delete(sapling_note_commitment_tree_cf, h);
-            self.zs_delete(orchard_note_commitment_tree_cf, h);
+        self.zs_insert(sapling_anchors, sapling_root, *height);
+        self.zs_insert(orchard_anchors, orchard_root, *height);
+
+        // Record any subtrees that were completed by this block's note commitments,
+        // so wallets can bootstrap sync from `z_getsubtreesbyindex` instead of
+        // scanning every note. Alongside each completed subtree's root, also
+        // persist a "cap": a full snapshot of the tree's frontier right after
+        // the subtree completed. This is the actual shard/cap storage split:
+        // completed shards are capped off here and never rewritten again, while
+        // `write_tree_shard` only records a leaf position checkpoint for these
+        // pools every block.
+        for (index, root) in note_commitment_trees.new_sapling_subtrees {
+            self.zs_insert(
+                sapling_subtree_cf,
+                index,
+                NoteCommitmentSubtreeData::new(*height, root),
+            );
+            self.zs_insert(
+                sapling_shard_cap_cf,
+                index,
+                note_commitment_trees.sapling.clone(),
+            );
+        }
+        for (index, root) in note_commitment_trees.new_orchard_subtrees {
+            self.zs_insert(
+                orchard_subtree_cf,
+                index,
+                NoteCommitmentSubtreeData::new(*height, root),
+            );
+            self.zs_insert(
+                orchard_shard_cap_cf,
+                index,
+                note_commitment_trees.orchard.clone(),
+            );
         }
 
-        self.zs_insert(
-            sprout_note_commitment_tree_cf,
-            height,
-            note_commitment_trees.sprout,
-        );
-
-        self.zs_insert(
-            sapling_note_commitment_tree_cf,
-            height,
-            note_commitment_trees.sapling,
-        );
+        // Record every note commitment appended by this block, along with its
+        // absolute leaf position, so a viewing-key scanner can trial-decrypt
+        // outputs and later build witnesses from the stored position.
+        for (position, commitment) in note_commitment_trees.new_sapling_commitments {
+            self.zs_insert(sapling_note_commitments_cf, position, (*height, commitment));
+        }
+        for (position, commitment) in note_commitment_trees.new_orchard_commitments {
+            self.zs_insert(orchard_note_commitments_cf, position, (*height, commitment));
+        }
 
-        self.zs_insert(
-            orchard_note_commitment_tree_cf,
-            height,
-            note_commitment_trees.orchard,
-        );
+        // Update the trees in state, recording a leaf position checkpoint for each
+        // pool, which tells a later rebuild how far to replay stored commitments.
+        self.write_tree_shard(db, *height, note_commitment_trees.sprout);
+        self.write_tree_shard(db, *height, note_commitment_trees.sapling);
+        self.write_tree_shard(db, *height, note_commitment_trees.orchard);
 
         self.prepare_history_batch(
             db,
@@ -315,4 +915,103 @@ impl DiskWriteBatch {
             orchard::tree::NoteCommitmentTree::default(),
         );
     }
+
+    /// Deletes any Sapling or Orchard subtree roots, note commitments, and tree
+    /// leaf position checkpoints recorded for a height greater than
+    /// `new_tip_height`, as part of rolling the finalized tip back to that
+    /// height.
+    ///
+    /// Without this, the `sapling_subtree`/`orchard_subtree` column families
+    /// (and the per-leaf commitment and checkpoint tables) would keep stale
+    /// entries for blocks that a reorg has since removed from the best chain.
+    ///
+    /// # Integration
+    ///
+    /// Like every other `prepare_*_batch` method in this module, this only
+    /// stages deletions into `self`; it must be called by the finalized
+    /// state's disconnect/rollback routine before `new_tip_height` is
+    /// committed as the new finalized tip, in the same batch that actually
+    /// rewinds the tip height itself.
+    pub fn prepare_subtree_rollback_batch(
+        &mut self,
+        db: &DiskDb,
+        zebra_db: &ZebraDb,
+        new_tip_height: block::Height,
+    ) {
+        let sapling_subtree_cf = db.cf_handle("sapling_subtree").unwrap();
+        let sapling_shard_cap_cf = db
+            .cf_handle("sapling_note_commitment_tree_shard_cap")
+            .unwrap();
+        for (index, subtree) in
+            zebra_db.sapling_subtree_list_by_index_range(NoteCommitmentSubtreeIndex(0), u16::MAX)
+        {
+            if subtree.end_height > new_tip_height {
+                self.zs_delete(sapling_subtree_cf, index);
+                self.zs_delete(sapling_shard_cap_cf, index);
+            }
+        }
+
+        let orchard_subtree_cf = db.cf_handle("orchard_subtree").unwrap();
+        let orchard_shard_cap_cf = db
+            .cf_handle("orchard_note_commitment_tree_shard_cap")
+            .unwrap();
+        for (index, subtree) in
+            zebra_db.orchard_subtree_list_by_index_range(NoteCommitmentSubtreeIndex(0), u16::MAX)
+        {
+            if subtree.end_height > new_tip_height {
+                self.zs_delete(orchard_subtree_cf, index);
+                self.zs_delete(orchard_shard_cap_cf, index);
+            }
+        }
+
+        let rollback_range =
+            (new_tip_height + 1).unwrap_or(new_tip_height)..=block::Height(u32::MAX);
+
+        let sapling_note_commitments_cf = db.cf_handle("sapling_note_commitments").unwrap();
+        for (position, _commitment) in
+            zebra_db.sapling_note_commitments_with_positions(rollback_range.clone())
+        {
+            self.zs_delete(sapling_note_commitments_cf, position);
+        }
+
+        let orchard_note_commitments_cf = db.cf_handle("orchard_note_commitments").unwrap();
+        for (position, _commitment) in
+            zebra_db.orchard_note_commitments_with_positions(rollback_range)
+        {
+            self.zs_delete(orchard_note_commitments_cf, position);
+        }
+
+        let sprout_checkpoint_cf = db
+            .cf_handle(sprout::tree::NoteCommitmentTree::CHECKPOINT_CF)
+            .unwrap();
+        for height in
+            zebra_db.tree_leaf_position_checkpoints_above::<sprout::tree::NoteCommitmentTree>(
+                new_tip_height,
+            )
+        {
+            self.zs_delete(sprout_checkpoint_cf, height);
+        }
+
+        let sapling_checkpoint_cf = db
+            .cf_handle(sapling::tree::NoteCommitmentTree::CHECKPOINT_CF)
+            .unwrap();
+        for height in
+            zebra_db.tree_leaf_position_checkpoints_above::<sapling::tree::NoteCommitmentTree>(
+                new_tip_height,
+            )
+        {
+            self.zs_delete(sapling_checkpoint_cf, height);
+        }
+
+        let orchard_checkpoint_cf = db
+            .cf_handle(orchard::tree::NoteCommitmentTree::CHECKPOINT_CF)
+            .unwrap();
+        for height in
+            zebra_db.tree_leaf_position_checkpoints_above::<orchard::tree::NoteCommitmentTree>(
+                new_tip_height,
+            )
+        {
+            self.zs_delete(orchard_checkpoint_cf, height);
+        }
+    }
 }
\ No newline at end of file
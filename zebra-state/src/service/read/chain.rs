@@ -3,9 +3,11 @@
 use std::sync::Arc;
 
 use zebra_chain::{
+    amount::NegativeAllowed,
     block::{Header, Height},
     history_tree::HistoryTree,
     parameters::POW_AVERAGING_WINDOW,
+    value_balance::ValueBalance,
 };
 
 use crate::{
@@ -32,6 +34,35 @@ where
         .or_else(|| Some(db.history_tree()))
 }
 
+/// Returns the running [`ValueBalance`] of every shielded and transparent
+/// value pool at the tip of the best chain.
+///
+/// The non-finalized `Chain`, when present, already carries a running total
+/// that covers every block back to genesis (it's seeded from the finalized
+/// tip's balance when the chain forked), so this only needs to read one
+/// source: `chain`'s tip balance if there's a non-finalized best chain, or
+/// `db`'s finalized tip balance otherwise.
+///
+/// This requires `non_finalized_state::Chain` to declare a `value_balance`
+/// field and update it on every block commit and rollback (mirroring how
+/// [`ZebraDb::finalized_value_pool`] is kept current on the finalized side).
+/// `Chain`'s definition isn't part of this source tree, so that tracking
+/// can't be added from this file; this function assumes it exists and reads
+/// it, the same way it already assumes and reads `chain.history_tree` above.
+pub fn value_balance<C>(chain: Option<C>, db: &ZebraDb) -> ValueBalance<NegativeAllowed>
+where
+    C: AsRef<Chain>,
+{
+    chain
+        .as_ref()
+        .map(|chain| chain.as_ref().value_balance)
+        .unwrap_or_else(|| {
+            db.finalized_value_pool()
+                .constrain()
+                .expect("the finalized tip balance is NonNegative, which always fits in NegativeAllowed")
+        })
+}
+
 /// 
 pub fn last_n_block_headers<C>(
     chain: Option<C>,